@@ -0,0 +1,709 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Error, Expr, ExprLit, Fields, GenericArgument,
+    Ident, Lifetime, LifetimeDef, Lit, LitInt, Meta, NestedMeta, Path, PathArguments, Type, WherePredicate,
+};
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                         #[derive({En,De}code,DecodeBorrow,Schema)]                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[proc_macro_derive(Encode, attributes(f0rm47))]
+pub fn derive_encode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    encode(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+#[proc_macro_derive(Decode, attributes(f0rm47))]
+pub fn derive_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    decode(&input, false).unwrap_or_else(Error::into_compile_error).into()
+}
+
+#[proc_macro_derive(DecodeBorrow, attributes(f0rm47))]
+pub fn derive_decode_borrow(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    decode(&input, true).unwrap_or_else(Error::into_compile_error).into()
+}
+
+#[proc_macro_derive(Schema, attributes(f0rm47))]
+pub fn derive_schema(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    schema(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        struct Attrs                                        │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+struct Attrs {
+    default: bool,
+    error: Option<Path>,
+    tag: Option<Ident>,
+    unknown: bool,
+    value: Option<LitInt>,
+}
+
+impl Attrs {
+    fn parse(attrs: &[Attribute]) -> Result<Self, Error> {
+        let mut this = Attrs { default: false, error: None, tag: None, unknown: false, value: None };
+
+        for attr in attrs {
+            if !attr.path.is_ident("f0rm47") {
+                continue;
+            }
+
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                meta => return Err(Error::new(meta.span(), "expected #[f0rm47(..)]")),
+            };
+
+            for nested in list.nested {
+                let pair = match nested {
+                    NestedMeta::Meta(Meta::NameValue(pair)) => pair,
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("default") => {
+                        this.default = true;
+                        continue;
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("unknown") => {
+                        this.unknown = true;
+                        continue;
+                    }
+                    nested => return Err(Error::new(nested.span(), "expected `key = value`, `default` or `unknown`")),
+                };
+
+                match (pair.path.get_ident().map(Ident::to_string).as_deref(), pair.lit) {
+                    (Some("error"), Lit::Str(lit)) => this.error = Some(lit.parse()?),
+                    (Some("tag"), Lit::Str(lit)) => this.tag = Some(lit.parse()?),
+                    (Some("tag"), Lit::Int(lit)) => this.value = Some(lit),
+                    (_, lit) => return Err(Error::new(lit.span(), "unknown or invalid attribute")),
+                }
+            }
+        }
+
+        Ok(this)
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        struct Input                                        │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+struct Input<'a> {
+    input: &'a DeriveInput,
+    error: Path,
+    tag: Ident,
+    variants: Vec<Variant<'a>>,
+    // Whether the enum has an `#[f0rm47(unknown)]` variant, in which case the payload of every
+    // other variant is prefixed with its length.
+    framed: bool,
+}
+
+struct Variant<'a> {
+    name: String,
+    path: TokenStream2,
+    tag: LitInt,
+    fields: &'a Fields,
+    // Whether each field is `#[f0rm47(default)]`.
+    defaults: Vec<bool>,
+    unknown: bool,
+}
+
+impl<'a> Input<'a> {
+    fn parse(input: &'a DeriveInput) -> Result<Self, Error> {
+        let attrs = Attrs::parse(&input.attrs)?;
+        if attrs.default {
+            return Err(Error::new(input.span(), "`default` is only supported on fields"));
+        } else if attrs.unknown {
+            return Err(Error::new(input.span(), "`unknown` is only supported on variants"));
+        }
+
+        let error = attrs.error.unwrap_or_else(|| parse_quote!(::f0rm47::Error));
+        let tag = attrs.tag.unwrap_or_else(|| Ident::new("u8", Span::call_site()));
+        let name = &input.ident;
+
+        let max = match tag.to_string().as_str() {
+            "u8" => u8::MAX as u64,
+            "u16" => u16::MAX as u64,
+            "u32" => u32::MAX as u64,
+            "u64" => u64::MAX,
+            _ => return Err(Error::new(tag.span(), "expected one of `u8`, `u16`, `u32` or `u64`")),
+        };
+
+        let mut variants = Vec::new();
+        match &input.data {
+            Data::Struct(data) => variants.push(Variant {
+                name: name.unraw().to_string(),
+                path: quote!(#name),
+                tag: LitInt::new("0", Span::call_site()),
+                fields: &data.fields,
+                defaults: defaults(&data.fields)?,
+                unknown: false,
+            }),
+            Data::Enum(data) => {
+                let mut next = Some(0u64);
+                let mut seen = Vec::new();
+
+                for variant in &data.variants {
+                    let vattrs = Attrs::parse(&variant.attrs)?;
+                    let ident = &variant.ident;
+                    if vattrs.default {
+                        return Err(Error::new(variant.span(), "`default` is only supported on fields"));
+                    } else if vattrs.unknown {
+                        if vattrs.value.is_some() || variant.discriminant.is_some() {
+                            return Err(Error::new(variant.span(), "the `unknown` variant can't have a tag"));
+                        } else if variant.fields.len() != 1 {
+                            return Err(Error::new(variant.span(), "expected a single `Unknown` field"));
+                        } else if variants.iter().any(|variant: &Variant| variant.unknown) {
+                            return Err(Error::new(variant.span(), "duplicate `unknown` variant"));
+                        }
+
+                        let ty = &variant.fields.iter().next().unwrap().ty;
+                        if unknown_tag(ty).is_some_and(|found| tag != found) {
+                            return Err(Error::new(ty.span(), format!("expected `Unknown<{}>`", tag)));
+                        }
+
+                        variants.push(Variant {
+                            name: ident.unraw().to_string(),
+                            path: quote!(#name::#ident),
+                            tag: LitInt::new("0", variant.span()),
+                            fields: &variant.fields,
+                            defaults: defaults(&variant.fields)?,
+                            unknown: true,
+                        });
+
+                        continue;
+                    }
+
+                    let value = match (vattrs.value, &variant.discriminant) {
+                        (Some(lit), _) => lit.base10_parse::<u64>()?,
+                        (None, Some((_, Expr::Lit(ExprLit { lit: Lit::Int(lit), .. })))) => {
+                            lit.base10_parse::<u64>()?
+                        }
+                        (None, Some((_, expr))) => {
+                            return Err(Error::new(expr.span(), "expected an integer discriminant"))
+                        }
+                        (None, None) => next.ok_or_else(|| Error::new(variant.span(), "tag overflow"))?,
+                    };
+
+                    if value > max {
+                        return Err(Error::new(variant.span(), format!("tag doesn't fit in `{}`", tag)));
+                    } else if seen.contains(&value) {
+                        return Err(Error::new(variant.span(), format!("duplicate tag `{}`", value)));
+                    }
+
+                    seen.push(value);
+                    next = value.checked_add(1);
+
+                    variants.push(Variant {
+                        name: ident.unraw().to_string(),
+                        path: quote!(#name::#ident),
+                        tag: LitInt::new(&format!("{}{}", value, tag), variant.span()),
+                        fields: &variant.fields,
+                        defaults: defaults(&variant.fields)?,
+                        unknown: false,
+                    });
+                }
+            }
+            Data::Union(_) => return Err(Error::new(input.span(), "unions are not supported")),
+        }
+
+        let framed = variants.iter().any(|variant| variant.unknown);
+        Ok(Input { input, error, tag, variants, framed })
+    }
+
+    fn has_defaults(&self) -> bool {
+        self.variants.iter().any(|variant| variant.defaults.contains(&true))
+    }
+
+    fn is_enum(&self) -> bool {
+        matches!(self.input.data, Data::Enum(_))
+    }
+
+    fn bounds(&self, decode: Option<TokenStream2>) -> Vec<WherePredicate> {
+        self.bounds_with(|ty, bounds| {
+            let error = &self.error;
+            bounds.push(parse_quote!(#ty: ::f0rm47::Encode));
+            bounds.push(parse_quote!(#error: ::core::convert::From<<#ty as ::f0rm47::Encode>::Error>));
+
+            if let Some(decode) = &decode {
+                bounds.push(parse_quote!(#ty: #decode));
+            }
+        })
+    }
+
+    fn bounds_with<F: FnMut(&Type, &mut Vec<WherePredicate>)>(&self, mut f: F) -> Vec<WherePredicate> {
+        let params = self.input.generics.type_params().map(|param| &param.ident).collect::<Vec<_>>();
+
+        let mut bounds = Vec::new();
+        for variant in &self.variants {
+            for field in variant.fields {
+                if mentions(field.ty.to_token_stream(), &params) {
+                    f(&field.ty, &mut bounds);
+                }
+            }
+        }
+
+        bounds
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                      fn {en,de}code()                                      │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+fn encode(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let input = Input::parse(input)?;
+    let is_enum = input.is_enum();
+    let error = &input.error;
+
+    let mut sizes = Vec::new();
+    let mut fast_sizes = Vec::new();
+    let mut encodes = Vec::new();
+
+    for variant in &input.variants {
+        let (pat, bindings) = pattern(variant, "__self_");
+        if input.framed && !variant.unknown {
+            let tag = &variant.tag;
+            let prefix = quote!(<::f0rm47::prefix::U32 as ::f0rm47::prefix::Prefix>);
+
+            sizes.push(quote! {
+                #pat => {
+                    let size = 0 #(+ ::f0rm47::Encode::size(#bindings)?)*;
+                    Ok(::f0rm47::Encode::fast_size(&#tag) + #prefix::fast_size(size) + size)
+                }
+            });
+
+            fast_sizes.push(quote! {
+                #pat => {
+                    let size = 0 #(+ ::f0rm47::Encode::fast_size(#bindings))*;
+                    ::f0rm47::Encode::fast_size(&#tag) + #prefix::fast_size(size) + size
+                }
+            });
+
+            encodes.push(quote! {
+                #pat => {
+                    ::f0rm47::Encode::encode_into(&#tag, &mut writer)?;
+                    #prefix::encode_into(0 #(+ ::f0rm47::Encode::size(#bindings)?)*, &mut writer)?;
+                    #(::f0rm47::Encode::encode_into(#bindings, &mut writer)?;)*
+                    Ok(())
+                }
+            });
+
+            continue;
+        }
+
+        // The `Unknown` field of the `unknown` variant encodes its own tag, which mustn't be the tag
+        // of a known variant since it would get decoded as that variant.
+        let mut check = quote!();
+        if variant.unknown {
+            let ty = &input.tag;
+            let unknown = &bindings[0];
+            let known = input.variants.iter().filter(|variant| !variant.unknown).map(|variant| &variant.tag);
+            let span = variant.fields.iter().next().unwrap().ty.span();
+            check = quote_spanned!(span=> let _: &::f0rm47::tagged::Unknown<#ty> = #unknown;);
+            if input.variants.len() > 1 {
+                check.extend(quote! {
+                    if [#(#known),*].contains(&#unknown.tag) {
+                        let err = ::f0rm47::Error::invalid_data("unknown variant with a known tag");
+                        return Err(<#error as ::core::convert::From<_>>::from(err));
+                    }
+                });
+            }
+        }
+
+        let tag = if is_enum && !variant.unknown { Some(&variant.tag) } else { None };
+        let tags = tag.iter();
+
+        sizes.push(quote! {
+            #pat => Ok(0 #(+ ::f0rm47::Encode::fast_size(&#tags))* #(+ ::f0rm47::Encode::size(#bindings)?)*),
+        });
+
+        let tags = tag.iter();
+        fast_sizes.push(quote! {
+            #pat => 0 #(+ ::f0rm47::Encode::fast_size(&#tags))* #(+ ::f0rm47::Encode::fast_size(#bindings))*,
+        });
+
+        let tags = tag.iter();
+        encodes.push(quote! {
+            #pat => {
+                #check
+                #(::f0rm47::Encode::encode_into(&#tags, &mut writer)?;)*
+                #(::f0rm47::Encode::encode_into(#bindings, &mut writer)?;)*
+                Ok(())
+            }
+        });
+    }
+
+    let name = &input.input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.input.generics.split_for_impl();
+    let mut where_clause = where_clause.cloned().unwrap_or_else(|| parse_quote!(where));
+    where_clause.predicates.extend(input.bounds(None));
+
+    Ok(quote! {
+        impl #impl_generics ::f0rm47::Encode for #name #ty_generics #where_clause {
+            type Error = #error;
+
+            fn size(&self) -> ::core::result::Result<usize, Self::Error> {
+                match self {
+                    #(#sizes)*
+                }
+            }
+
+            fn fast_size(&self) -> usize {
+                match self {
+                    #(#fast_sizes)*
+                }
+            }
+
+            #[allow(unused_mut, unused_variables)]
+            fn encode_into<__W: ::f0rm47::io::Write>(&self, mut writer: __W) -> ::core::result::Result<(), Self::Error> {
+                match self {
+                    #(#encodes)*
+                }
+            }
+        }
+    })
+}
+
+fn decode(input: &DeriveInput, borrow: bool) -> Result<TokenStream2, Error> {
+    let input = Input::parse(input)?;
+    let error = &input.error;
+    let tag = &input.tag;
+
+    let lifetime = Lifetime::new("'__de", Span::call_site());
+    let (decode, decode_field) = if borrow {
+        (
+            quote!(::f0rm47::DecodeBorrow<#lifetime>),
+            quote!(decode_borrow_with_read(&buf[read..])),
+        )
+    } else {
+        (quote!(::f0rm47::Decode), quote!(decode_with_read_from(&mut reader)))
+    };
+
+    let ty_name = input.input.ident.unraw().to_string();
+    let mut fallback = quote!(tag => Err(::f0rm47::Error::invalid_tag(#ty_name, tag as u64).into()));
+    let mut decodes = Vec::new();
+    for variant in &input.variants {
+        if variant.unknown {
+            let path = &variant.path;
+            let payload = if borrow { quote!(payload.to_vec()) } else { quote!(payload) };
+            let unknown = quote!(::f0rm47::tagged::Unknown { tag, payload: #payload });
+            let val = match variant.fields.iter().next().and_then(|field| field.ident.as_ref()) {
+                Some(ident) => quote!(#path { #ident: #unknown }),
+                None => quote!(#path(#unknown)),
+            };
+
+            fallback = quote!(tag => Ok((#val, end)));
+            continue;
+        }
+
+        let (pat, bindings) = pattern(variant, "__field_");
+        let tys = variant.fields.iter().map(|field| &field.ty).collect::<Vec<&Type>>();
+        let segments = variant.fields.iter().enumerate().map(|(idx, field)| match &field.ident {
+            Some(ident) => {
+                let name = ident.unraw().to_string();
+                quote!(::f0rm47::Segment::Field(#name))
+            }
+            None => quote!(::f0rm47::Segment::Index(#idx)),
+        });
+
+        let fields = tys.iter().zip(segments).zip(&variant.defaults).map(|((ty, segment), default)| {
+            let field = quote! {{
+                let (val, readb) = <#ty as #decode>::#decode_field.map_err(|err| {
+                    ::f0rm47::Trace::trace(<#error as ::core::convert::From<_>>::from(err), Some(#segment), read)
+                })?;
+                read += readb;
+                val
+            }};
+
+            // A bounded input (see `Decode::decode_body()`) ending right before a `default` field
+            // means that it was written before the field was added. Ending anywhere inside of it,
+            // or ending an unbounded input, is still an error.
+            if !*default {
+                field
+            } else if borrow {
+                quote! {
+                    if bounded && buf.len() == read {
+                        ::core::default::Default::default()
+                    } else #field
+                }
+            } else {
+                quote! {
+                    match ::f0rm47::envelope::decode_default::<#ty, #error, _>(&mut reader, bounded) {
+                        Ok((val, readb)) => {
+                            read += readb;
+                            val
+                        }
+                        Err(err) => return Err(::f0rm47::Trace::trace(err, Some(#segment), read)),
+                    }
+                }
+            }
+        });
+
+        let read = if input.framed { quote!(end) } else { quote!(read) };
+        let body = quote! {
+            #(let #bindings = #fields;)*
+
+            Ok((#pat, #read))
+        };
+
+        if input.is_enum() {
+            let value = &variant.tag;
+            decodes.push(quote!(#value => { #body }));
+        } else {
+            decodes.push(body);
+        }
+    }
+
+    let body = if input.is_enum() {
+        let decode_tag = if borrow { quote!(decode_with_read(buf)) } else { quote!(decode_with_read_from(&mut reader)) };
+
+        // The fields of framed variants are decoded out of their payload, ignoring any trailing
+        // fields added by newer versions of the variant.
+        let payload = match (input.framed, borrow) {
+            (false, _) => quote!(),
+            (true, false) => quote! {
+                let (payload, readb) = ::f0rm47::tagged::decode_payload(&mut reader).map_err(|err| {
+                    ::f0rm47::Trace::trace(<#error as ::core::convert::From<_>>::from(err), None, read)
+                })?;
+                read += readb;
+                let end = read + payload.len();
+                let mut reader = &payload[..];
+                let bounded = true;
+            },
+            (true, true) => quote! {
+                let (payload, readb) = ::f0rm47::tagged::borrow_payload(&buf[read..]).map_err(|err| {
+                    ::f0rm47::Trace::trace(<#error as ::core::convert::From<_>>::from(err), None, read)
+                })?;
+                read += readb;
+                let end = read + payload.len();
+                let buf = &buf[..end];
+                let bounded = true;
+            },
+        };
+
+        quote! {
+            let (tag, mut read) = <#tag as ::f0rm47::Decode>::#decode_tag?;
+            #payload
+            match tag {
+                #(#decodes)*
+                #fallback,
+            }
+        }
+    } else {
+        quote! {
+            let mut read = 0;
+            #(#decodes)*
+        }
+    };
+
+    let name = &input.input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.input.generics.split_for_impl();
+    let mut where_clause = where_clause.cloned().unwrap_or_else(|| parse_quote!(where));
+    where_clause.predicates.extend(input.bounds(Some(decode.clone())));
+    if input.has_defaults() {
+        where_clause.predicates.push(parse_quote!(#error: ::f0rm47::Incomplete));
+        for variant in &input.variants {
+            for (field, _) in variant.fields.iter().zip(&variant.defaults).filter(|(_, default)| **default) {
+                let ty = &field.ty;
+                where_clause.predicates.push(parse_quote!(#ty: ::core::default::Default));
+            }
+        }
+    }
+
+    if borrow {
+        // `'__de` must outlive every lifetime of the type so that its fields can borrow from the
+        // input buffer.
+        let mut generics = input.input.generics.clone();
+        let mut param = LifetimeDef::new(lifetime.clone());
+        param.bounds.extend(generics.lifetimes().map(|def| def.lifetime.clone()));
+        generics.params.insert(0, param.into());
+        let (impl_generics, _, _) = generics.split_for_impl();
+
+        return Ok(quote! {
+            impl #impl_generics #decode for #name #ty_generics #where_clause {
+                #[allow(unused_mut, unused_variables)]
+                fn decode_borrow_with_read(
+                    buf: &#lifetime [u8],
+                ) -> ::core::result::Result<(Self, usize), Self::Error> {
+                    let _depth = ::f0rm47::limits::Depth::enter()?;
+                    let bounded = false;
+                    #body
+                }
+            }
+        });
+    }
+
+    // Only types with `default` fields decode bounded inputs differently.
+    let decode_body = if input.has_defaults() {
+        quote! {
+            #[allow(unused_mut, unused_variables)]
+            fn decode_body(body: &[u8]) -> ::core::result::Result<(Self, usize), Self::Error> {
+                let _depth = ::f0rm47::limits::Depth::enter()?;
+                let mut reader = body;
+                let bounded = true;
+                #body
+            }
+        }
+    } else {
+        quote!()
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::f0rm47::Decode for #name #ty_generics #where_clause {
+            #[allow(unused_mut, unused_variables)]
+            fn decode_with_read_from<__R: ::f0rm47::io::Read>(
+                mut reader: __R,
+            ) -> ::core::result::Result<(Self, usize), Self::Error> {
+                let _depth = ::f0rm47::limits::Depth::enter()?;
+                let bounded = false;
+                #body
+            }
+
+            #decode_body
+        }
+    })
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        fn schema()                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+fn schema(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let input = Input::parse(input)?;
+
+    let mut variants = Vec::new();
+    for variant in input.variants.iter().filter(|variant| !variant.unknown) {
+        let fields = variant.fields.iter().enumerate().map(|(idx, field)| {
+            let name = match &field.ident {
+                Some(ident) => ident.unraw().to_string(),
+                None => idx.to_string(),
+            };
+
+            let ty = &field.ty;
+            quote!(::f0rm47::schema::Field { name: #name, layout: <#ty as ::f0rm47::Schema>::schema() })
+        });
+
+        let name = &variant.name;
+        let tag = variant.tag.base10_parse::<u64>()?;
+        let fields = quote!(::core::convert::From::from([#(#fields),*]));
+        variants.push((quote!(::f0rm47::schema::Variant { name: #name, tag: #tag, fields: #fields }), fields));
+    }
+
+    let name = input.input.ident.unraw().to_string();
+    let body = if input.is_enum() {
+        let tag = &input.tag;
+        let variants = variants.iter().map(|(variant, _)| variant);
+        let prefix = if input.framed {
+            quote!(::core::option::Option::Some(::core::convert::From::from(<::f0rm47::prefix::U32 as ::f0rm47::Schema>::schema())))
+        } else {
+            quote!(::core::option::Option::None)
+        };
+
+        quote! {
+            ::f0rm47::schema::Layout::Enum {
+                name: #name,
+                tag: ::core::convert::From::from(<#tag as ::f0rm47::Schema>::schema()),
+                prefix: #prefix,
+                variants: ::core::convert::From::from([#(#variants),*]),
+            }
+        }
+    } else {
+        let fields = &variants[0].1;
+        quote!(::f0rm47::schema::Layout::Struct { name: #name, fields: #fields })
+    };
+
+    let ident = &input.input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.input.generics.split_for_impl();
+    let mut where_clause = where_clause.cloned().unwrap_or_else(|| parse_quote!(where));
+    where_clause.predicates.extend(input.bounds_with(|ty, bounds| bounds.push(parse_quote!(#ty: ::f0rm47::Schema))));
+
+    Ok(quote! {
+        impl #impl_generics ::f0rm47::Schema for #ident #ty_generics #where_clause {
+            fn schema() -> ::f0rm47::schema::Layout {
+                #body
+            }
+        }
+    })
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Helpers                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// The `G` of an `Unknown<G>` field, if its type is spelled out as such.
+fn unknown_tag(ty: &Type) -> Option<String> {
+    let segment = match ty {
+        Type::Path(path) => path.path.segments.last()?,
+        _ => return None,
+    };
+
+    match &segment.arguments {
+        _ if segment.ident != "Unknown" => None,
+        PathArguments::None => Some("u8".to_owned()),
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(ty) => Some(ty.to_token_stream().to_string()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn pattern(variant: &Variant, prefix: &str) -> (TokenStream2, Vec<Ident>) {
+    let path = &variant.path;
+    let bindings = (0..variant.fields.len()).map(|idx| format_ident!("{}{}", prefix, idx)).collect::<Vec<_>>();
+
+    let pat = match variant.fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|field| &field.ident);
+            quote!(#path { #(#names: #bindings),* })
+        }
+        Fields::Unnamed(_) => quote!(#path(#(#bindings),*)),
+        Fields::Unit => quote!(#path),
+    };
+
+    (pat, bindings)
+}
+
+fn defaults(fields: &Fields) -> Result<Vec<bool>, Error> {
+    let mut defaults = Vec::new();
+    for field in fields {
+        let attrs = Attrs::parse(&field.attrs)?;
+        if attrs.error.is_some() || attrs.tag.is_some() || attrs.value.is_some() || attrs.unknown {
+            return Err(Error::new(field.span(), "only `default` is supported on fields"));
+        } else if !attrs.default && defaults.last() == Some(&true) {
+            return Err(Error::new(field.span(), "fields following a `default` field must be `default` too"));
+        }
+
+        defaults.push(attrs.default);
+    }
+
+    Ok(defaults)
+}
+
+fn mentions(tokens: TokenStream2, params: &[&Ident]) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => params.contains(&&ident),
+        TokenTree::Group(group) => mentions(group.stream(), params),
+        _ => false,
+    })
+}
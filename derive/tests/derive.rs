@@ -0,0 +1,302 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use f0rm47::envelope::{Envelope, Versioned};
+use f0rm47::limits::{DecodeLimits, Limit};
+use f0rm47::schema::{self, Change, Layout, Step};
+use f0rm47::tagged::Unknown;
+use f0rm47::{Decode, DecodeBorrow, Encode, ErrorKind, Schema, Segment};
+use std::borrow::Cow;
+use std::net::IpAddr;
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                           Types                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[derive(Encode, Decode, Schema, Debug, PartialEq)]
+struct Named {
+    foo: u32,
+    bar: Option<u8>,
+    baz: Vec<u16>,
+}
+
+#[derive(Encode, Decode, Schema, Debug, PartialEq)]
+struct Unnamed(u8, i64);
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Unit;
+
+#[derive(Encode, Decode, DecodeBorrow, Schema, Debug, PartialEq)]
+struct Generic<T> {
+    val: T,
+    vals: Vec<T>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum Auto {
+    Foo,
+    Bar(u16),
+    Baz { writer: u8, reader: u8 },
+}
+
+#[derive(Encode, Decode, Schema, Debug, PartialEq)]
+#[f0rm47(tag = "u16")]
+enum Explicit {
+    #[f0rm47(tag = 4)]
+    Foo,
+    #[f0rm47(tag = 6)]
+    Bar(u8),
+    Baz,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+enum Discriminant {
+    Foo = 2,
+    Bar,
+}
+
+#[derive(Encode, DecodeBorrow, Debug, PartialEq)]
+struct Borrowed<'a> {
+    id: u32,
+    data: &'a [u8],
+    name: &'a str,
+    cow: Cow<'a, [u8]>,
+    chunks: Vec<&'a [u8]>,
+}
+
+#[derive(Encode, DecodeBorrow, Schema, Debug, PartialEq)]
+enum Message<'a, 'b> {
+    Ping,
+    Data(&'a [u8], Generic<&'b str>),
+}
+
+#[allow(dead_code)]
+#[derive(Schema)]
+struct NamedV2 {
+    id: u32,
+    bar: Option<u8>,
+    baz: Vec<u32>,
+    extra: bool,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+struct Peer {
+    id: u32,
+    #[f0rm47(default)]
+    port: u16,
+    #[f0rm47(default)]
+    tags: Vec<String>,
+}
+
+impl Versioned for Peer {
+    const VERSION: u16 = 3;
+}
+
+#[derive(Encode, Decode, DecodeBorrow, Schema, Debug, PartialEq)]
+#[f0rm47(tag = "u16")]
+enum Event {
+    Ping,
+    Data(u32),
+    #[f0rm47(unknown)]
+    Other(Unknown<u16>),
+}
+
+#[derive(Encode, Decode, Schema, Debug, PartialEq)]
+#[f0rm47(tag = "u16")]
+enum EventV2 {
+    Ping,
+    Data(u32, #[f0rm47(default)] u8),
+    Join { id: u64 },
+    #[f0rm47(unknown)]
+    Other { unknown: Unknown<u16> },
+}
+
+#[allow(dead_code)]
+#[derive(Schema)]
+enum Addr {
+    #[f0rm47(tag = 4)]
+    V4([u8; 4]),
+    #[f0rm47(tag = 6)]
+    V6([u8; 16]),
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[test]
+fn structs() {
+    let named = Named { foo: 42, bar: Some(1), baz: vec![1, 2, 3] };
+    let encoded = named.encode().unwrap();
+    assert_eq!(encoded, (42u32, Some(1u8), vec![1u16, 2, 3]).encode().unwrap());
+    assert_eq!(encoded.len(), named.size().unwrap());
+    assert_eq!(Named::decode_with_read(&encoded).unwrap(), (named, encoded.len()));
+
+    let err = Named::decode(&[42, 0, 0, 0, 2]).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidTag { ty: "bool", tag: 2 }));
+    assert_eq!(err.path().collect::<Vec<_>>(), [&Segment::Field("bar")]);
+    assert_eq!(err.offset(), 4);
+
+    let unnamed = Unnamed(1, -1);
+    let encoded = unnamed.encode().unwrap();
+    assert_eq!(encoded, (1u8, -1i64).encode().unwrap());
+    assert_eq!(Unnamed::decode(&encoded).unwrap(), unnamed);
+
+    assert_eq!(Unit.fast_size(), 0);
+    assert!(Unit.encode().unwrap().is_empty());
+    assert_eq!(Unit::decode_with_read(&[]).unwrap(), (Unit, 0));
+}
+
+#[test]
+fn generics() {
+    let generic = Generic { val: 1u64, vals: vec![2, 3] };
+    let encoded = generic.encode().unwrap();
+    assert_eq!(encoded, (1u64, vec![2u64, 3]).encode().unwrap());
+    assert_eq!(Generic::<u64>::decode(&encoded).unwrap(), generic);
+}
+
+#[test]
+fn enums() {
+    assert_eq!(Auto::Foo.encode().unwrap(), [0]);
+    assert_eq!(Auto::Bar(1).encode().unwrap(), [1, 1, 0]);
+    assert_eq!(Auto::Baz { writer: 1, reader: 2 }.encode().unwrap(), [2, 1, 2]);
+    assert_eq!(Auto::decode(&[2, 1, 2]).unwrap(), Auto::Baz { writer: 1, reader: 2 });
+    assert!(matches!(Auto::decode(&[3]).unwrap_err().kind(), ErrorKind::InvalidTag { ty: "Auto", tag: 3 }));
+
+    assert_eq!(Explicit::Foo.encode().unwrap(), [4, 0]);
+    assert_eq!(Explicit::Bar(1).encode().unwrap(), [6, 0, 1]);
+    assert_eq!(Explicit::Baz.encode().unwrap(), [7, 0]);
+    assert_eq!(Explicit::decode_from(&[6u8, 0, 1][..]).unwrap(), Explicit::Bar(1));
+    assert!(Explicit::decode(&[5, 0]).is_err());
+
+    assert_eq!(Discriminant::Bar.encode().unwrap(), [3]);
+    assert_eq!(Discriminant::decode(&[2]).unwrap(), Discriminant::Foo);
+}
+
+#[test]
+fn limits() {
+    let named = Named { foo: 42, bar: Some(1), baz: vec![1, 2, 3] };
+    let encoded = named.encode().unwrap();
+
+    let limits = DecodeLimits { max_depth: 2, ..Default::default() };
+    assert_eq!(limits.decode::<Named>(&encoded).unwrap(), named);
+
+    let limits = DecodeLimits { max_depth: 1, ..Default::default() };
+    let err = limits.decode::<Named>(&encoded).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::LimitExceeded { limit: Limit::Depth, max: 1 }));
+    assert_eq!(err.path().collect::<Vec<_>>(), [&Segment::Field("bar")]);
+    assert_eq!(err.offset(), 4);
+}
+
+#[test]
+fn borrowed() {
+    let borrowed = Borrowed { id: 1, data: b"abc", name: "foo", cow: Cow::Borrowed(b"de"), chunks: vec![b"f", b"gh"] };
+    let encoded = borrowed.encode().unwrap();
+    assert_eq!(encoded, (1u32, &b"abc"[..], "foo", &b"de"[..], vec![&b"f"[..], b"gh"]).encode().unwrap());
+    assert_eq!(Borrowed::decode_borrow_with_read(&encoded).unwrap(), (borrowed, encoded.len()));
+
+    let decoded = Borrowed::decode_borrow(&encoded).unwrap();
+    assert_eq!(decoded.data.as_ptr(), encoded[6..].as_ptr());
+    assert!(matches!(decoded.cow, Cow::Borrowed(_)));
+
+    let err = Borrowed::decode_borrow(&encoded[..encoded.len() - 1]).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::UnexpectedEof { needed: 1 }));
+    assert_eq!(err.path().collect::<Vec<_>>(), [&Segment::Field("chunks"), &Segment::Index(1)]);
+
+    let message = Message::Data(b"abc", Generic { val: "foo", vals: vec!["bar"] });
+    let encoded = message.encode().unwrap();
+    assert_eq!(Message::decode_borrow(&encoded).unwrap(), message);
+    assert_eq!(Message::decode_borrow(&[0]).unwrap(), Message::Ping);
+    assert!(matches!(Message::decode_borrow(&[2]).unwrap_err().kind(), ErrorKind::InvalidTag { ty: "Message", tag: 2 }));
+}
+
+#[test]
+fn schemas() {
+    assert_eq!(schema::check(&Named::schema(), &<(u32, Option<u8>, Vec<u16>)>::schema()), []);
+    assert_eq!(schema::check(&IpAddr::schema(), &Addr::schema()), []);
+    assert_eq!(Generic::<u8>::schema(), Generic::<u8>::schema());
+    assert!(matches!(Unnamed::schema(), Layout::Struct { name: "Unnamed", fields } if fields[1].name == "1"));
+
+    let found = schema::check(&Named::schema(), &NamedV2::schema());
+    assert_eq!(found[0].change, Change::Fields { old: 3, new: 4 });
+    assert_eq!(found[1].path, [Step::Field("baz"), Step::Elem]);
+    assert_eq!(found.len(), 2);
+
+    let found = schema::check(&Explicit::schema(), &Message::schema());
+    assert_eq!(found[0].path, [Step::Tag]);
+    assert_eq!(found.iter().filter(|found| matches!(found.change, Change::VariantRemoved { .. })).count(), 3);
+    assert_eq!(found.iter().filter(|found| matches!(found.change, Change::VariantAdded { .. })).count(), 2);
+}
+
+#[test]
+fn defaults() {
+    assert_eq!(Peer::decode_body(&[1, 0, 0, 0]).unwrap(), (Peer { id: 1, port: 0, tags: vec![] }, 4));
+    assert_eq!(Peer::decode_body(&[1, 0, 0, 0, 2, 0]).unwrap(), (Peer { id: 1, port: 2, tags: vec![] }, 6));
+    assert!(Peer::decode_body(&[1, 0, 0]).is_err());
+
+    // Ending partway through a `default` field doesn't default it.
+    let err = Peer::decode_body(&[1, 0, 0, 0, 2]).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::UnexpectedEof { needed: 1 }));
+    assert_eq!(err.path().copied().collect::<Vec<_>>(), [Segment::Field("port")]);
+    assert_eq!(err.offset(), 4);
+    assert!(Peer::decode_body(&[1, 0, 0, 0, 2, 0, 1]).is_err());
+
+    // Neither does ending an input which might have held more than the value.
+    let err = Peer::decode(&[1, 0, 0, 0]).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::UnexpectedEof { needed: 2 }));
+    assert_eq!(err.path().copied().collect::<Vec<_>>(), [Segment::Field("port")]);
+    assert!(Peer::decode_from(&[1, 0, 0, 0][..]).is_err());
+    assert!(<(Peer, u8)>::decode_body(&[1, 0, 0, 0, 3]).is_err());
+
+    let peer = Envelope(Peer { id: 1, port: 2, tags: vec!["foo".to_owned()] });
+    assert_eq!(Envelope::<Peer>::decode(&peer.encode().unwrap()).unwrap(), peer);
+    assert_eq!(Envelope::<Peer>::decode(&[1, 0, 4, 0, 0, 0, 1, 0, 0, 0]).unwrap().0.port, 0);
+
+    let err = Envelope::<Peer>::decode(&[2, 0, 5, 0, 0, 0, 1, 0, 0, 0, 2]).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::UnexpectedEof { needed: 1 }));
+    assert_eq!(err.offset(), 10);
+}
+
+#[test]
+fn unknown() {
+    let encoded = Event::Data(7).encode().unwrap();
+    assert_eq!(encoded, [1, 0, 4, 0, 0, 0, 7, 0, 0, 0]);
+    assert_eq!(Event::decode(&encoded).unwrap(), Event::Data(7));
+    assert_eq!(EventV2::decode(&encoded).unwrap(), EventV2::Data(7, 0));
+    assert_eq!(Event::decode(&Event::Ping.encode().unwrap()).unwrap(), Event::Ping);
+
+    let encoded = EventV2::Join { id: 9 }.encode().unwrap();
+    let event = Event::decode(&encoded).unwrap();
+    assert_eq!(event, Event::Other(Unknown { tag: 2, payload: vec![9, 0, 0, 0, 0, 0, 0, 0] }));
+    assert_eq!(Event::decode_borrow(&encoded).unwrap(), event);
+    assert_eq!(event.encode().unwrap(), encoded);
+
+    let encoded = EventV2::Data(7, 1).encode().unwrap();
+    assert_eq!(Event::decode_with_read(&encoded).unwrap(), (Event::Data(7), 11));
+    assert_eq!(Event::decode_borrow_with_read(&encoded).unwrap(), (Event::Data(7), 11));
+
+    let err = Event::decode(&[1, 0, 2, 0, 0, 0, 7, 0]).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::UnexpectedEof { .. }));
+    assert_eq!(err.path().copied().collect::<Vec<_>>(), [Segment::Index(0)]);
+    assert_eq!(err.offset(), 6);
+
+    let found = schema::check(&Event::schema(), &EventV2::schema());
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].path, [Step::Variant("Data")]);
+
+    // An `Unknown` with the tag of a known variant would be decoded as that variant.
+    let err = Event::Other(Unknown { tag: 1, payload: vec![7, 0, 0, 0] }).encode().unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidData { .. }));
+
+    let results = vec![Ok(1u8), Err("foo".to_owned())];
+    assert_eq!(Vec::<Result<u8, String>>::decode(&results.encode().unwrap()).unwrap(), results);
+}
@@ -0,0 +1,327 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use crate::buf::Buffer;
+use crate::frame::{Decoded, FrameDecoder};
+use crate::limits::{self, DecodeLimits, Limit};
+use crate::prefix::Prefix;
+use crate::{Decode, Encode, Error, Incomplete};
+use alloc::vec::Vec;
+use core::future::poll_fn;
+use core::pin::Pin;
+use core::task::Poll;
+use std::io;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                     trait AsyncEncode                                      │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[allow(async_fn_in_trait)]
+pub trait AsyncEncode: Encode {
+    async fn encode_into_async<W: AsyncWrite + Unpin + ?Sized>(&self, writer: &mut W) -> Result<(), Self::Error>;
+}
+
+impl<T> AsyncEncode for T
+where
+    T: Encode + ?Sized,
+    T::Error: From<Error>,
+{
+    async fn encode_into_async<W: AsyncWrite + Unpin + ?Sized>(&self, writer: &mut W) -> Result<(), Self::Error> {
+        let encoded = self.encode()?;
+        writer.write_all(&encoded).await.map_err(Error::from)?;
+
+        Ok(())
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                   struct StreamDecoder<R>                                  │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Decodes consecutive values from `reader`. Values are decoded out of a buffer which is retried
+// whenever it at least doubled in size, or `reader` ran out of bytes for now, so that a value
+// only gets decoded O(log n) times while it's received in one go. Bytes read past a value stay
+// buffered for the next one, see `StreamDecoder::buffered()`.
+//
+// A peer sending a value in many small writes can still trigger a retry after each of them:
+// prefer `decode_frame_from_async()` for untrusted ones.
+#[derive(Debug)]
+pub struct StreamDecoder<R> {
+    reader: R,
+    buf: Buffer,
+    limits: DecodeLimits,
+    eof: bool,
+}
+
+impl<R> StreamDecoder<R> {
+    pub fn new(reader: R) -> Self {
+        StreamDecoder::with_limits(reader, DecodeLimits::default())
+    }
+
+    pub fn with_limits(reader: R, limits: DecodeLimits) -> Self {
+        StreamDecoder { reader, buf: Buffer::default(), limits, eof: false }
+    }
+
+    pub fn buffered(&self) -> &[u8] {
+        &self.buf
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    pub fn into_parts(self) -> (R, Vec<u8>) {
+        (self.reader, self.buf.into_vec())
+    }
+}
+
+impl<R: AsyncRead + Unpin> StreamDecoder<R> {
+    pub async fn decode<T>(&mut self) -> Result<T, T::Error>
+    where
+        T: Decode,
+        T::Error: From<Error> + Incomplete,
+    {
+        Ok(self.decode_with_read::<T>().await?.0)
+    }
+
+    pub async fn decode_with_read<T>(&mut self) -> Result<(T, usize), T::Error>
+    where
+        T: Decode,
+        T::Error: From<Error> + Incomplete,
+    {
+        loop {
+            let needed = match self.limits.decode_with_read::<T>(&self.buf) {
+                Ok((val, read)) => {
+                    self.buf.consume(read);
+                    return Ok((val, read));
+                }
+                Err(err) if self.eof => return Err(err),
+                Err(err) => err.needed().ok_or(err)?,
+            };
+
+            let min = self.buf.len().saturating_add(needed);
+            if min > self.limits.max_bytes {
+                return Err(limits::exceeded(Limit::Bytes, self.limits.max_bytes).into());
+            }
+
+            let max = min.max(self.buf.len().saturating_mul(2)).min(self.limits.max_bytes);
+            self.eof = fill(&mut self.reader, &mut self.buf, min, max).await.map_err(Error::from)?;
+        }
+    }
+}
+
+// Reads into `buf` until it holds at least `min` bytes, then keeps reading up to `max` bytes for
+// as long as `reader` has some ready. Returns whether `reader` reached its end. `buf` only ever
+// holds the bytes read so far, should the returned future get dropped.
+async fn fill<R>(reader: &mut R, buf: &mut Buffer, min: usize, max: usize) -> io::Result<bool>
+where
+    R: AsyncRead + Unpin + ?Sized,
+{
+    poll_fn(|cx| {
+        let mut len = buf.len();
+        let poll = loop {
+            if len >= max {
+                break Poll::Ready(Ok(false));
+            } else if buf.len() == len {
+                buf.resize(max.min(len + limits::MAX_PREALLOC));
+            }
+
+            let mut read_buf = ReadBuf::new(&mut buf[len..]);
+            match Pin::new(&mut *reader).poll_read(cx, &mut read_buf) {
+                Poll::Ready(Ok(())) if read_buf.filled().is_empty() => break Poll::Ready(Ok(true)),
+                Poll::Ready(Ok(())) => len += read_buf.filled().len(),
+                Poll::Ready(Err(err)) => break Poll::Ready(Err(err)),
+                Poll::Pending if len >= min => break Poll::Ready(Ok(false)),
+                Poll::Pending => break Poll::Pending,
+            }
+        };
+
+        buf.truncate(len);
+        poll
+    })
+    .await
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                fn decode_frame_from_async()                                │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Decodes a frame written by `frame::encode_frame()`, only decoding its body once it was fully
+// received.
+pub async fn decode_frame_from_async<P, T, R>(reader: &mut R, max_len: usize) -> Result<T, T::Error>
+where
+    P: Prefix,
+    T: Decode,
+    T::Error: From<Error> + Incomplete,
+    R: AsyncRead + Unpin + ?Sized,
+{
+    let mut decoder = FrameDecoder::<T, P>::with_max_len(max_len);
+    let mut chunk = Vec::new();
+    loop {
+        let needed = match decoder.decode()? {
+            Decoded::Frame(val) => return Ok(val),
+            Decoded::NeedMore(needed) => needed,
+        };
+
+        chunk.resize(needed.min(limits::MAX_PREALLOC), 0);
+        let read = reader.read(&mut chunk).await.map_err(Error::from)?;
+        if read == 0 {
+            return Err(Error::unexpected_eof(needed).into());
+        }
+
+        decoder.extend(&chunk[..read]);
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(test)]
+#[tokio::test]
+async fn stream() {
+    use crate::ErrorKind;
+    use tokio::io::BufReader;
+
+    let mut buf = Vec::new();
+    (1u8, 2u32).encode_into_async(&mut buf).await.unwrap();
+    "hello".encode_into_async(&mut buf).await.unwrap();
+    [3u64; 4].encode_into_async(&mut buf).await.unwrap();
+
+    let mut decoder = StreamDecoder::new(BufReader::with_capacity(3, &buf[..]));
+    assert_eq!(decoder.decode_with_read::<(u8, u32)>().await.unwrap(), ((1, 2), 5));
+    assert_eq!(decoder.decode::<String>().await.unwrap(), "hello");
+    assert_eq!(decoder.decode::<[u64; 4]>().await.unwrap(), [3; 4]);
+    assert!(decoder.buffered().is_empty());
+
+    let err = StreamDecoder::new(&buf[..2]).decode::<u32>().await.unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::UnexpectedEof { needed: 2 }));
+
+    // Bytes read past a value are kept for the next one.
+    let mut decoder = StreamDecoder::new(&buf[..]);
+    assert_eq!(decoder.decode::<(u8, u32)>().await.unwrap(), (1, 2));
+    let (reader, buffered) = decoder.into_parts();
+    assert_eq!([&buffered[..], reader].concat(), &buf[5..]);
+
+    let limits = DecodeLimits { max_bytes: 4, ..Default::default() };
+    let err = StreamDecoder::with_limits(&buf[5..], limits).decode::<String>().await.unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::LimitExceeded { limit: Limit::Bytes, max: 4 }));
+    let limits = DecodeLimits { max_len: 4, ..Default::default() };
+    let err = StreamDecoder::with_limits(&buf[5..], limits).decode::<String>().await.unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::LimitExceeded { limit: Limit::Len, max: 4 }));
+
+    // Large values are only decoded a few times, however small the reads.
+    let elems = vec![7u32; 60_000];
+    let buf = elems.encode().unwrap();
+    let mut decoder = StreamDecoder::new(BufReader::with_capacity(3, &buf[..]));
+    assert_eq!(decoder.decode::<Vec<u32>>().await.unwrap(), elems);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn pending() {
+    use tokio::io::duplex;
+
+    // A value is decoded as soon as it was received, without waiting on more bytes.
+    let (mut writer, reader) = duplex(64);
+    let mut decoder = StreamDecoder::new(reader);
+    (1u8, 2u32).encode_into_async(&mut writer).await.unwrap();
+    assert_eq!(decoder.decode::<(u8, u32)>().await.unwrap(), (1, 2));
+
+    "hello".encode_into_async(&mut writer).await.unwrap();
+    3u16.encode_into_async(&mut writer).await.unwrap();
+    assert_eq!(decoder.decode::<String>().await.unwrap(), "hello");
+    assert_eq!(decoder.decode::<u16>().await.unwrap(), 3);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn frames() {
+    use crate::frame::encode_frame;
+    use crate::prefix::U32;
+
+    let mut buf = encode_frame::<U32, _>(&vec![1u32; 1000]).unwrap();
+    buf.extend(encode_frame::<U32, _>(&7u8).unwrap());
+
+    let mut reader = &buf[..];
+    assert_eq!(decode_frame_from_async::<U32, Vec<u32>, _>(&mut reader, 4096).await.unwrap(), vec![1; 1000]);
+    assert_eq!(decode_frame_from_async::<U32, u8, _>(&mut reader, 4096).await.unwrap(), 7);
+    assert!(reader.is_empty());
+
+    assert!(decode_frame_from_async::<U32, Vec<u32>, _>(&mut &buf[..], 16).await.is_err());
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn defaults() {
+    use crate::envelope::decode_default;
+    use crate::frame::encode_frame;
+    use crate::io::Read;
+    use crate::prefix::U32;
+    use crate::ErrorKind;
+    use tokio::io::BufReader;
+
+    #[derive(Debug, PartialEq)]
+    struct Peer {
+        ip: u32,
+        port: u16,
+    }
+
+    impl Peer {
+        fn decode_fields<R: Read>(mut reader: R, bounded: bool) -> Result<(Self, usize), Error> {
+            let (ip, read1) = u32::decode_with_read_from(&mut reader)?;
+            let (port, read2) = decode_default::<u16, Error, _>(reader, bounded)?;
+            Ok((Peer { ip, port }, read1 + read2))
+        }
+    }
+
+    impl Encode for Peer {
+        type Error = Error;
+
+        fn fast_size(&self) -> usize {
+            self.ip.fast_size() + self.port.fast_size()
+        }
+
+        fn encode_into<W: crate::io::Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+            self.ip.encode_into(&mut writer)?;
+            self.port.encode_into(writer)
+        }
+    }
+
+    // Like a derived type whose `port` is a `#[f0rm47(default)]` field.
+    impl Decode for Peer {
+        fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+            Peer::decode_fields(reader, false)
+        }
+
+        fn decode_body(body: &[u8]) -> Result<(Self, usize), Self::Error> {
+            Peer::decode_fields(body, true)
+        }
+    }
+
+    let mut buf = Vec::new();
+    Peer { ip: 1, port: 2 }.encode_into_async(&mut buf).await.unwrap();
+    Peer { ip: 3, port: 4 }.encode_into_async(&mut buf).await.unwrap();
+
+    let mut decoder = StreamDecoder::new(BufReader::with_capacity(3, &buf[..]));
+    assert_eq!(decoder.decode_with_read::<Peer>().await.unwrap(), (Peer { ip: 1, port: 2 }, 6));
+    assert_eq!(decoder.decode::<Peer>().await.unwrap(), Peer { ip: 3, port: 4 });
+
+    // A stream could always hold more of the value, so `port` is only defaulted inside of a frame.
+    let err = StreamDecoder::new(&buf[..4]).decode::<Peer>().await.unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::UnexpectedEof { needed: 2 }));
+
+    let frame = encode_frame::<U32, _>(&1u32).unwrap();
+    let peer = decode_frame_from_async::<U32, Peer, _>(&mut &frame[..], 16).await.unwrap();
+    assert_eq!(peer, Peer { ip: 1, port: 0 });
+}
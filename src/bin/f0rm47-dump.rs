@@ -0,0 +1,59 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use f0rm47::dump::{self, Registry};
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime};
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                         fn main()                                          │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Only knows about the types provided by this crate. Applications should register their own types
+// and call `dump::cli()` from their own binary.
+fn main() -> ExitCode {
+    let mut registry = Registry::new();
+    registry
+        .register::<u8>("u8")
+        .register::<u16>("u16")
+        .register::<u32>("u32")
+        .register::<u64>("u64")
+        .register::<u128>("u128")
+        .register::<i8>("i8")
+        .register::<i16>("i16")
+        .register::<i32>("i32")
+        .register::<i64>("i64")
+        .register::<i128>("i128")
+        .register::<f32>("f32")
+        .register::<f64>("f64")
+        .register::<bool>("bool")
+        .register::<char>("char")
+        .register::<String>("String")
+        .register::<[u8]>("bytes")
+        .register::<Duration>("Duration")
+        .register::<SystemTime>("SystemTime");
+
+    #[cfg(feature = "net")]
+    {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+        registry
+            .register::<IpAddr>("IpAddr")
+            .register::<Ipv4Addr>("Ipv4Addr")
+            .register::<Ipv6Addr>("Ipv6Addr")
+            .register::<SocketAddr>("SocketAddr")
+            .register::<SocketAddrV4>("SocketAddrV4")
+            .register::<SocketAddrV6>("SocketAddrV6");
+    }
+
+    dump::cli(&registry)
+}
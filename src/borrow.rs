@@ -0,0 +1,241 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use crate::io::Write;
+use crate::limits::Depth;
+use crate::{Decode, DecodeRef, Encode, Error, Segment, Trace};
+use alloc::borrow::{Cow, ToOwned};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                     trait DecodeBorrow                                     │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Decodes a value which may borrow from the input buffer (e.g. `&'de [u8]`, `&'de str` or
+// `Cow<'de, [u8]>`) instead of copying out of it. Types which don't borrow simply decode as
+// usual.
+pub trait DecodeBorrow<'de>: Encode + Sized {
+    fn decode_borrow(buf: &'de [u8]) -> Result<Self, Self::Error> {
+        Ok(Self::decode_borrow_with_read(buf)?.0)
+    }
+
+    fn decode_borrow_with_read(buf: &'de [u8]) -> Result<(Self, usize), Self::Error>;
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                           Macros                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+macro_rules! owned {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl<'de> $crate::borrow::DecodeBorrow<'de> for $ty {
+                fn decode_borrow_with_read(buf: &'de [u8]) -> Result<(Self, usize), Self::Error> {
+                    <$ty as $crate::Decode>::decode_with_read(buf)
+                }
+            }
+        )+
+    };
+}
+
+pub(crate) use owned;
+
+macro_rules! tuple {
+    ($($idx:tt: $name:ident),+) => {
+        impl<'de, Err, $($name),+> DecodeBorrow<'de> for ($($name),+)
+        where
+            $($name: Encode<Error = Err> + DecodeBorrow<'de>,)+
+            Err: Trace,
+        {
+            fn decode_borrow_with_read(buf: &'de [u8]) -> Result<(Self, usize), Self::Error> {
+                let mut len = 0;
+                let val = (
+                    $({
+                        let (val, read) = <$name>::decode_borrow_with_read(&buf[len..])
+                            .map_err(|err| err.trace(Some(Segment::Index($idx)), len))?;
+                        len += read;
+                        val
+                    },)+
+                );
+
+                Ok((val, len))
+            }
+        }
+    };
+}
+
+macro_rules! tuples {
+    ($idx1:tt: $name1:ident, $idx2:tt: $name2:ident $(, $($idx:tt: $name:ident),+)?) => {
+        tuples!(@INTERNAL; ($idx1: $name1, $idx2: $name2) ($($($idx: $name),+)?));
+    };
+
+    (@INTERNAL;
+     ($($idx:tt: $name:ident),+) ()
+    ) => {
+        tuple!($($idx: $name),+);
+    };
+
+    (@INTERNAL;
+     ($($idx:tt: $name:ident),+) ($oidx:tt: $oname:ident $(, $($ridx:tt: $rname:ident),+)?)
+    ) => {
+        tuple!($($idx: $name),+);
+        tuples!(@INTERNAL; ($($idx: $name),+, $oidx: $oname) ($($($ridx: $rname),+)?));
+    };
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                         owned!(..)                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+owned!(u8, u16, u32, u64, u128, usize);
+owned!(i8, i16, i32, i64, i128, isize);
+owned!(f32, f64, bool, char, String);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        tuples!(..)                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+tuples!(0: A, 1: B, 2: C, 3: D, 4: E, 5: F, 6: G, 7: H, 8: I, 9: J, 10: K, 11: L, 12: M);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                             impl DecodeBorrow for &[u8], &str                              │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl<'de: 'a, 'a> DecodeBorrow<'de> for &'a [u8] {
+    fn decode_borrow_with_read(buf: &'de [u8]) -> Result<(Self, usize), Self::Error> {
+        <[u8]>::decode_ref_with_read(buf)
+    }
+}
+
+impl<'de: 'a, 'a> DecodeBorrow<'de> for &'a str {
+    fn decode_borrow_with_read(buf: &'de [u8]) -> Result<(Self, usize), Self::Error> {
+        str::decode_ref_with_read(buf)
+    }
+}
+
+impl<'de, const LEN: usize> DecodeBorrow<'de> for [u8; LEN] {
+    fn decode_borrow_with_read(buf: &'de [u8]) -> Result<(Self, usize), Self::Error> {
+        Self::decode_with_read(buf)
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                           impl {Encode,DecodeBorrow} for Cow<B>                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl<B: Encode + ToOwned + ?Sized> Encode for Cow<'_, B> {
+    type Error = B::Error;
+
+    fn size(&self) -> Result<usize, Self::Error> {
+        (**self).size()
+    }
+
+    fn fast_size(&self) -> usize {
+        (**self).fast_size()
+    }
+
+    fn encode(&self) -> Result<Vec<u8>, Self::Error> {
+        (**self).encode()
+    }
+
+    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+        (**self).encode_into(writer)
+    }
+}
+
+impl<'de: 'a, 'a> DecodeBorrow<'de> for Cow<'a, [u8]> {
+    fn decode_borrow_with_read(buf: &'de [u8]) -> Result<(Self, usize), Self::Error> {
+        let (buf, read) = <[u8]>::decode_ref_with_read(buf)?;
+        Ok((Cow::Borrowed(buf), read))
+    }
+}
+
+impl<'de: 'a, 'a> DecodeBorrow<'de> for Cow<'a, str> {
+    fn decode_borrow_with_read(buf: &'de [u8]) -> Result<(Self, usize), Self::Error> {
+        let (str, read) = str::decode_ref_with_read(buf)?;
+        Ok((Cow::Borrowed(str), read))
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                   impl DecodeBorrow for Box<T>, Option<T>, Result<T, E>                    │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl<'de, T: DecodeBorrow<'de>> DecodeBorrow<'de> for Box<T>
+where
+    T::Error: From<Error>,
+{
+    fn decode_borrow_with_read(buf: &'de [u8]) -> Result<(Self, usize), Self::Error> {
+        let _depth = Depth::enter()?;
+        let (val, len) = T::decode_borrow_with_read(buf)?;
+        Ok((Box::new(val), len))
+    }
+}
+
+impl<'de, T: DecodeBorrow<'de>> DecodeBorrow<'de> for Option<T>
+where
+    T::Error: From<Error> + Trace,
+{
+    fn decode_borrow_with_read(buf: &'de [u8]) -> Result<(Self, usize), Self::Error> {
+        match bool::decode_with_read(buf)? {
+            (true, read1) => {
+                let _depth = Depth::enter()?;
+                let (val, read2) = T::decode_borrow_with_read(&buf[read1..]).map_err(|err| err.trace(None, read1))?;
+                Ok((Some(val), read1 + read2))
+            }
+            (false, read) => Ok((None, read)),
+        }
+    }
+}
+
+impl<'de, Err, T, E> DecodeBorrow<'de> for Result<T, E>
+where
+    T: Encode<Error = Err> + DecodeBorrow<'de>,
+    E: Encode<Error = Err> + DecodeBorrow<'de>,
+    Err: From<Error> + Trace,
+{
+    fn decode_borrow_with_read(buf: &'de [u8]) -> Result<(Self, usize), Self::Error> {
+        let (tag, read1) = u8::decode_with_read(buf)?;
+        let _depth = Depth::enter()?;
+        let (val, read2) = match tag {
+            0 => T::decode_borrow_with_read(&buf[read1..]).map(|(val, read)| (Ok(val), read)),
+            1 => E::decode_borrow_with_read(&buf[read1..]).map(|(err, read)| (Err(err), read)),
+            _ => return Err(Error::invalid_tag("Result", tag as u64).into()),
+        }
+        .map_err(|err| err.trace(None, read1))?;
+
+        Ok((val, read1 + read2))
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(test)]
+#[test]
+fn borrow() {
+    use crate::ErrorKind;
+
+    let encoded = (7u8, &b"abc"[..], "héllo", Some(Cow::Borrowed(&b"de"[..]))).encode().unwrap();
+    let (tag, bytes, str, cow) = <(u8, &[u8], &str, Option<Cow<[u8]>>)>::decode_borrow(&encoded).unwrap();
+    assert_eq!((tag, bytes, str), (7, &b"abc"[..], "héllo"));
+    assert!(matches!(cow, Some(Cow::Borrowed(b"de"))));
+    assert_eq!(bytes.as_ptr(), encoded[3..].as_ptr());
+
+    let err = <(u8, &str)>::decode_borrow(&encoded[..4]).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::UnexpectedEof { needed: 2 }));
+    assert_eq!(err.path().copied().collect::<Vec<_>>(), [Segment::Index(1)]);
+    assert_eq!(err.offset(), 3);
+}
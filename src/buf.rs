@@ -0,0 +1,128 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut};
+
+#[cfg(feature = "secrets")]
+use zeroize::Zeroize;
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                       struct Buffer                                        │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// The buffer of `FrameDecoder` and `aio::StreamDecoder`. With the `secrets` feature, every byte
+// it stops holding is zeroed: when it's consumed, truncated, moved by a reallocation or dropped.
+#[derive(Debug, Default)]
+pub(crate) struct Buffer(Vec<u8>);
+
+impl Buffer {
+    pub(crate) fn extend_from_slice(&mut self, bytes: &[u8]) {
+        let len = self.0.len();
+        self.resize(len + bytes.len());
+        self.0[len..].copy_from_slice(bytes);
+    }
+
+    // Extends the buffer with zeroes.
+    pub(crate) fn resize(&mut self, len: usize) {
+        #[cfg(feature = "secrets")]
+        if len > self.0.capacity() {
+            let mut grown = Vec::with_capacity(len.max(self.0.capacity() * 2));
+            grown.extend_from_slice(&self.0);
+            core::mem::swap(&mut self.0, &mut grown);
+            grown.zeroize();
+        }
+
+        if len > self.0.len() {
+            self.0.resize(len, 0);
+        }
+    }
+
+    pub(crate) fn truncate(&mut self, len: usize) {
+        #[cfg(feature = "secrets")]
+        if len < self.0.len() {
+            self.0[len..].zeroize();
+        }
+
+        self.0.truncate(len);
+    }
+
+    // Removes the first `len` bytes.
+    pub(crate) fn consume(&mut self, len: usize) {
+        self.0.copy_within(len.., 0);
+        self.truncate(self.0.len() - len);
+    }
+
+    #[cfg(feature = "tokio")]
+    pub(crate) fn into_vec(mut self) -> Vec<u8> {
+        core::mem::take(&mut self.0)
+    }
+}
+
+impl Clone for Buffer {
+    fn clone(&self) -> Self {
+        Buffer(self.0.as_slice().into())
+    }
+}
+
+impl Deref for Buffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl DerefMut for Buffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+#[cfg(feature = "secrets")]
+impl Drop for Buffer {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(test)]
+#[test]
+fn buffer() {
+    let mut buf = Buffer::default();
+    buf.extend_from_slice(&[1, 2, 3]);
+    buf.resize(5);
+    assert_eq!(&buf[..], [1, 2, 3, 0, 0]);
+
+    buf.consume(2);
+    assert_eq!(&buf[..], [3, 0, 0]);
+    buf.truncate(1);
+    buf.extend_from_slice(&[4]);
+    assert_eq!(&buf.clone()[..], [3, 4]);
+}
+
+#[cfg(all(test, feature = "secrets"))]
+#[test]
+fn zeroized() {
+    let mut buf = Buffer::default();
+    buf.extend_from_slice(&[1, 2, 3, 4]);
+    buf.consume(3);
+    assert_eq!(&buf[..], [4]);
+
+    // The bytes past the end of the buffer were zeroed.
+    let spare = buf.0.spare_capacity_mut();
+    assert!(spare[..3].iter().all(|byte| unsafe { byte.assume_init() } == 0));
+}
@@ -0,0 +1,184 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use crate::borrow::owned;
+use crate::io::{Read, Write};
+use crate::schema::{Layout, Schema};
+use crate::{Decode, Encode, Error};
+use cfg_if::cfg_if;
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                       cfg_if! { .. }                                       │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+cfg_if! {
+    if #[cfg(feature = "collections")] {
+        use alloc::vec::Vec;
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                    struct Canonical<T>                                     │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Canonical<T>(pub T);
+
+// Encodes like `Canonical<T>`, but decoding fails unless the input is canonical itself.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Strict<T>(pub T);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                 fn {sorted,check_sorted}()                                 │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(feature = "collections")]
+pub(crate) fn sorted<'a, T, X, I>(entries: I) -> Result<Vec<(Vec<u8>, X)>, T::Error>
+where
+    T: Encode + 'a,
+    I: Iterator<Item = (&'a T, X)>,
+{
+    let mut sorted = Vec::new();
+    for (key, extra) in entries {
+        sorted.push((key.encode()?, extra));
+    }
+
+    sorted.sort_unstable_by(|(key1, _), (key2, _)| key1.cmp(key2));
+    Ok(sorted)
+}
+
+#[cfg(feature = "collections")]
+pub(crate) fn check_sorted<T: Encode>(prev: &mut Option<Vec<u8>>, key: &T) -> Result<(), T::Error>
+where
+    T::Error: From<Error>,
+{
+    let encoded = key.encode()?;
+    match prev {
+        Some(prev) if encoded == *prev => Err(Error::invalid_data("duplicate key").into()),
+        Some(prev) if encoded < *prev => Err(Error::invalid_data("unsorted keys").into()),
+        _ => {
+            *prev = Some(encoded);
+            Ok(())
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                           Macros                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+macro_rules! float {
+    ($float:ty, $nan:expr) => {
+        impl Canonical<$float> {
+            fn canonicalize(val: $float) -> $float {
+                if val.is_nan() {
+                    <$float>::from_bits($nan)
+                } else if val == 0.0 {
+                    0.0
+                } else {
+                    val
+                }
+            }
+        }
+
+        impl Encode for Canonical<$float> {
+            type Error = Error;
+
+            fn fast_size(&self) -> usize {
+                self.0.fast_size()
+            }
+
+            fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+                Self::canonicalize(self.0).encode_into(writer)
+            }
+        }
+
+        impl Decode for Canonical<$float> {
+            fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+                let (val, read) = <$float>::decode_with_read_from(reader)?;
+                Ok((Canonical(Self::canonicalize(val)), read))
+            }
+        }
+    };
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                         float!(..)                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+float!(f32, 0x7fc0_0000);
+float!(f64, 0x7ff8_0000_0000_0000);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                         owned!(..)                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+owned!(Canonical<f32>, Canonical<f64>);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                           impl Schema for {Canonical,Strict}<T>                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl<T: Schema> Schema for Canonical<T> {
+    fn schema() -> Layout {
+        T::schema()
+    }
+}
+
+impl<T: Schema> Schema for Strict<T> {
+    fn schema() -> Layout {
+        T::schema()
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(test)]
+#[test]
+fn floats() {
+    assert_eq!(1.5f32.encode().unwrap(), 1.5f32.to_le_bytes());
+    assert_eq!(f64::decode(&(-2.25f64).encode().unwrap()).unwrap(), -2.25);
+
+    let nan = f64::from_bits(0x7ff8_0000_dead_beef);
+    assert_ne!(nan.encode().unwrap(), f64::NAN.encode().unwrap());
+    assert_eq!(Canonical(nan).encode().unwrap(), f64::NAN.encode().unwrap());
+    assert_eq!(Canonical(-f32::NAN).encode().unwrap(), f32::NAN.encode().unwrap());
+    assert_eq!(Canonical(-0.0f32).encode().unwrap(), 0.0f32.encode().unwrap());
+
+    let decoded = Canonical::<f64>::decode(&nan.encode().unwrap()).unwrap();
+    assert_eq!(decoded.0.to_bits(), f64::NAN.to_bits());
+}
+
+#[cfg(all(test, feature = "collections", feature = "std"))]
+#[test]
+fn maps() {
+    use crate::ErrorKind;
+    use std::collections::{BTreeMap, HashMap};
+
+    let map = BTreeMap::from([(1u16, true), (256, false)]);
+    let encoded = Canonical(map.clone()).encode().unwrap();
+    assert_eq!(encoded, [2, 0, 0, 1, 0, 1, 0, 1]);
+    assert_eq!(Canonical(map.iter().map(|(k, v)| (*k, *v)).collect::<HashMap<_, _>>()).encode().unwrap(), encoded);
+    assert_eq!(Strict::<HashMap<u16, bool>>::decode(&encoded).unwrap().0.len(), 2);
+
+    let unsorted = map.encode().unwrap();
+    assert_eq!(Canonical::<BTreeMap<u16, bool>>::decode(&unsorted).unwrap().0, map);
+    let err = Strict::<BTreeMap<u16, bool>>::decode(&unsorted).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidData { context: "unsorted keys" }));
+    assert_eq!(err.offset(), 5);
+
+    let duplicate = [2, 0, 1, 0, 1, 1, 0, 0];
+    let err = Strict::<HashMap<u16, bool>>::decode(&duplicate).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidData { context: "duplicate key" }));
+}
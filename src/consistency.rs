@@ -0,0 +1,237 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use crate::canonical::Canonical;
+use crate::endian::BigEndian;
+use crate::varint::{Varint, ZigZag};
+use crate::{Decode, DecodeRef};
+use core::fmt::Debug;
+use quickcheck::quickcheck;
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        fn check*()                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Checks that `encode()` and `encode_into()` agree, that `size()` and (if `exact`) `fast_size()`
+// match the length of the encoded value, and that decoding it reads back exactly that length.
+pub(crate) fn check_with<T>(val: &T, exact: bool) -> bool
+where
+    T: Decode + Debug + PartialEq,
+    T::Error: Debug,
+{
+    let encoded = val.encode().unwrap();
+
+    let mut buf = Vec::new();
+    val.encode_into(&mut buf).unwrap();
+    assert_eq!(encoded, buf, "encode() != encode_into()");
+
+    assert_eq!(val.size().unwrap(), encoded.len(), "size() != len");
+    if exact {
+        assert_eq!(val.fast_size(), encoded.len(), "fast_size() != len");
+    }
+
+    buf.extend_from_slice(&[0xff; 3]);
+    for (decoded, read) in [T::decode_with_read(&buf).unwrap(), T::decode_with_read_from(&buf[..]).unwrap()] {
+        assert_eq!(read, encoded.len(), "read != len");
+        if contains_nan(val) {
+            assert_eq!(decoded.encode().unwrap(), encoded, "decoded != val");
+        } else {
+            assert_eq!(decoded, *val, "decoded != val");
+        }
+    }
+
+    true
+}
+
+// Only values containing a NaN aren't equal to themselves, so those can only be compared to their
+// decoded counterpart through their encodings.
+#[allow(clippy::eq_op)]
+fn contains_nan<T: PartialEq>(val: &T) -> bool {
+    val != val
+}
+
+pub(crate) fn check<T>(val: &T) -> bool
+where
+    T: Decode + Debug + PartialEq,
+    T::Error: Debug,
+{
+    check_with(val, true)
+}
+
+pub(crate) fn check_ref<T>(val: &T) -> bool
+where
+    T: DecodeRef + Debug + PartialEq + ?Sized,
+    T::Error: Debug,
+{
+    let encoded = val.encode().unwrap();
+    assert_eq!(val.size().unwrap(), encoded.len(), "size() != len");
+    assert_eq!(val.fast_size(), encoded.len(), "fast_size() != len");
+
+    let mut buf = encoded.clone();
+    buf.extend_from_slice(&[0xff; 3]);
+
+    let (decoded, read) = T::decode_ref_with_read(&buf).unwrap();
+    assert_eq!(read, encoded.len(), "read != len");
+    assert_eq!(decoded, val, "decoded != val");
+
+    true
+}
+
+#[cfg(all(feature = "collections", feature = "std"))]
+fn check_heap(heap: std::collections::BinaryHeap<u16>) -> bool {
+    use crate::Encode;
+
+    let encoded = heap.encode().unwrap();
+    assert_eq!(heap.size().unwrap(), encoded.len(), "size() != len");
+    assert_eq!(heap.fast_size(), encoded.len(), "fast_size() != len");
+
+    let (decoded, read) = std::collections::BinaryHeap::<u16>::decode_with_read(&encoded).unwrap();
+    assert_eq!(read, encoded.len(), "read != len");
+    assert_eq!(decoded.into_sorted_vec(), heap.into_sorted_vec(), "decoded != val");
+
+    true
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        quickcheck!                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+quickcheck! {
+    fn unsigned(a: u8, b: u16, c: u32, d: u64, e: u128) -> bool {
+        check(&a) && check(&b) && check(&c) && check(&d) && check(&e)
+    }
+
+    fn signed(a: i8, b: i16, c: i32, d: i64, e: i128) -> bool {
+        check(&a) && check(&b) && check(&c) && check(&d) && check(&e)
+    }
+
+    fn others(a: bool, b: usize, c: isize, d: char, e: f32, f: f64) -> bool {
+        check(&a) && check(&b) && check(&c) && check(&d) && check(&e) && check(&f)
+    }
+
+    fn wrappers(a: u64, b: i32, c: usize, d: f64, e: u16) -> bool {
+        check(&Varint(a)) && check(&ZigZag(b)) && check(&Varint(c))
+            && check(&Canonical(d)) && check(&BigEndian(e)) && check(&BigEndian(b))
+    }
+
+    fn options(a: Option<u32>, b: Option<Option<bool>>, c: Box<i64>) -> bool {
+        check(&a) && check(&b) && check(&c)
+    }
+
+    fn tuples(a: (u8, u16), b: (u8, i16, u32, i64, bool)) -> bool {
+        check(&a) && check(&b)
+    }
+
+    fn arrays(a: (u16, u16, u16, u16), b: (u8, u8, u8)) -> bool {
+        check(&[a.0, a.1, a.2, a.3]) && check(&[b.0, b.1, b.2]) && check(&[a, a])
+    }
+
+    fn text(a: String, b: Vec<u8>) -> bool {
+        check(&a) && check_ref(a.as_str()) && check_ref(b.as_slice())
+    }
+}
+
+#[cfg(feature = "collections")]
+quickcheck! {
+    fn vecs(a: Vec<u8>, b: Vec<u32>, c: Vec<String>, d: Vec<Vec<u8>>) -> bool {
+        check(&a) && check(&b) && check_with(&c, false) && check_with(&d, false)
+    }
+
+    fn prefixed(a: Vec<u8>, b: Vec<u32>) -> bool {
+        use crate::prefix::{Prefixed, Varint, U32, U64, U8};
+
+        check(&Prefixed::<_, U32>::new(a.clone()))
+            && check(&Prefixed::<_, U64>::new(b.clone()))
+            && check(&Prefixed::<_, Varint>::new(a.clone()))
+            && check(&Prefixed::<_, BigEndian<U32>>::new(b))
+            && (a.len() > u8::MAX as usize || check(&Prefixed::<_, U8>::new(a)))
+    }
+}
+
+#[cfg(all(feature = "collections", feature = "std"))]
+quickcheck! {
+    fn collections(a: Vec<u16>, b: Vec<(u8, u64)>) -> bool {
+        use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque};
+
+        check(&a.iter().copied().collect::<VecDeque<_>>())
+            && check(&a.iter().copied().collect::<LinkedList<_>>())
+            && check_heap(a.iter().copied().collect())
+            && check(&a.iter().copied().collect::<BTreeSet<_>>())
+            && check(&a.iter().copied().collect::<HashSet<_>>())
+            && check(&b.iter().copied().collect::<BTreeMap<_, _>>())
+            && check(&b.iter().copied().collect::<HashMap<_, _>>())
+    }
+
+    fn canonical(a: Vec<(u16, u8)>, b: Vec<i32>) -> bool {
+        use crate::canonical::Strict;
+        use crate::Encode;
+        use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+        let map = a.iter().copied().collect::<HashMap<_, _>>();
+        let set = b.iter().copied().collect::<HashSet<_>>();
+        let bmap = Canonical(a.iter().copied().collect::<BTreeMap<_, _>>());
+        let bset = Canonical(b.iter().copied().collect::<BTreeSet<_>>());
+
+        assert_eq!(Canonical(map.clone()).encode().ok(), Some(bmap.encode().unwrap()));
+        assert_eq!(Canonical(set.clone()).encode().ok(), Some(bset.encode().unwrap()));
+
+        check(&Canonical(map.clone())) && check(&Strict(map)) && check(&Strict(set)) && check(&Strict(bmap.0))
+            && check(&bset)
+    }
+}
+
+quickcheck! {
+    fn duration(secs: u64, nanos: u32) -> bool {
+        check(&core::time::Duration::new(secs, nanos % 1_000_000_000))
+    }
+}
+
+#[cfg(feature = "std")]
+quickcheck! {
+    fn system_time(secs: i64, nanos: u32) -> bool {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        // Times before `UNIX_EPOCH` with a subsecond part are encoded with a positive number of
+        // nanoseconds, and thus one more negative second.
+        let since = Duration::new((secs % 1_000_000_000_000).unsigned_abs(), nanos % 1_000_000_000);
+        let time = if secs < 0 { UNIX_EPOCH - since } else { UNIX_EPOCH + since };
+
+        check(&time) && check(&(UNIX_EPOCH - Duration::from_secs(since.as_secs())))
+    }
+}
+
+#[cfg(feature = "net")]
+quickcheck! {
+    fn net(a: std::net::IpAddr, b: std::net::SocketAddr) -> bool {
+        check(&a) && check(&b) && check(&BigEndian(b))
+    }
+}
+
+#[cfg(feature = "chrono")]
+quickcheck! {
+    fn chrono(days: i32, secs: u32, nanos: u32, offset: i32, duration: (i64, u32)) -> bool {
+        use chrono::{Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+
+        // Nanoseconds past 1,000,000,000 are leap seconds, which only happen at the end of a minute.
+        let nanos = nanos % 2_000_000_000;
+        let secs = if nanos >= 1_000_000_000 { secs % 86_400 / 60 * 60 + 59 } else { secs % 86_400 };
+
+        let date = NaiveDate::from_num_days_from_ce_opt(days % 3_000_000).unwrap();
+        let time = NaiveTime::from_num_seconds_from_midnight_opt(secs, nanos).unwrap();
+        let offset = FixedOffset::east_opt(offset % 86_400).unwrap();
+        let datetime = offset.from_utc_datetime(&NaiveDateTime::new(date, time));
+        let duration = Duration::new(duration.0 % 1_000_000_000_000, duration.1 % 1_000_000_000).unwrap();
+
+        check(&date) && check(&time) && check(&datetime.naive_utc()) && check(&datetime) && check(&duration)
+            && check(&-duration)
+    }
+}
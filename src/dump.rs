@@ -0,0 +1,583 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use crate::schema::{Endian, Layout, Schema, Step};
+use crate::{varint, Error, Trace};
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use cfg_if::cfg_if;
+use core::convert::TryInto;
+use core::fmt::{self, Display, Formatter};
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                       cfg_if! { .. }                                       │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+cfg_if! {
+    if #[cfg(feature = "std")] {
+        use std::io::Read as _;
+        use std::process::ExitCode;
+        use std::{env, fs, io};
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        struct Dump                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Every value decoded while walking a buffer against a type's `Layout`, in order. Its `Display`
+// impl renders an annotated hexdump of the buffer.
+#[derive(Debug)]
+pub struct Dump<'a> {
+    buf: &'a [u8],
+    entries: Vec<Entry>,
+    read: usize,
+    error: Option<(Vec<Step>, Error)>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Entry {
+    // Outermost step first.
+    pub path: Vec<Step>,
+    pub offset: usize,
+    pub len: usize,
+    pub ty: String,
+    // `None` for structs, tuples, sequences, etc.
+    pub value: Option<String>,
+}
+
+impl<'a> Dump<'a> {
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn read(&self) -> usize {
+        self.read
+    }
+
+    pub fn error(&self) -> Option<(&[Step], &Error)> {
+        self.error.as_ref().map(|(path, err)| (&path[..], err))
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                      fn dump{,_as}()                                       │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+pub fn dump<T: Schema + ?Sized>(buf: &[u8]) -> Dump<'_> {
+    dump_as(&T::schema(), buf)
+}
+
+pub fn dump_as<'a>(layout: &Layout, buf: &'a [u8]) -> Dump<'a> {
+    let mut walker = Walker { buf, read: 0, path: Vec::new(), entries: Vec::new() };
+    let error = walker.walk(layout).err().map(|err| (walker.path.clone(), err));
+
+    Dump { buf, entries: walker.entries, read: walker.read, error }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                       struct Walker                                        │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+struct Walker<'a> {
+    buf: &'a [u8],
+    read: usize,
+    path: Vec<Step>,
+    entries: Vec<Entry>,
+}
+
+impl<'a> Walker<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let left = self.buf.len() - self.read;
+        if left < len {
+            Err(Error::unexpected_eof(len - left).trace(None, self.read))
+        } else {
+            self.read += len;
+            Ok(&self.buf[self.read - len..self.read])
+        }
+    }
+
+    // Returns the decoded integer for integers, varints and booleans, so that it can be used as a
+    // length prefix or an enum tag.
+    fn walk(&mut self, layout: &Layout) -> Result<Option<u128>, Error> {
+        let offset = self.read;
+        let idx = self.entries.len();
+        self.entries.push(Entry { path: self.path.clone(), offset, len: 0, ty: ty(layout), value: None });
+
+        let (int, value) = match layout {
+            Layout::Bool => match self.take(1)?[0] {
+                0 => (Some(0), Some("false".to_string())),
+                1 => (Some(1), Some("true".to_string())),
+                tag => return Err(Error::invalid_tag("bool", tag as u64).trace(None, offset)),
+            },
+            Layout::Char => {
+                let val = u32::from_le_bytes(self.take(4)?.try_into().unwrap());
+                match char::from_u32(val) {
+                    Some(chr) => (None, Some(format!("{:?}", chr))),
+                    None => return Err(Error::invalid_data("invalid char").trace(None, offset)),
+                }
+            }
+            Layout::Int { size, signed, endian } => {
+                if !(1..=16).contains(size) {
+                    return Err(Error::invalid_data("unsupported int size").trace(None, offset));
+                }
+
+                let mut bytes = [0; 16];
+                bytes[..*size].copy_from_slice(self.take(*size)?);
+                if *endian == Endian::Big {
+                    bytes[..*size].reverse();
+                }
+
+                let val = u128::from_le_bytes(bytes);
+                if *signed {
+                    let shift = 128 - size * 8;
+                    (Some(val), Some((((val << shift) as i128) >> shift).to_string()))
+                } else {
+                    (Some(val), Some(val.to_string()))
+                }
+            }
+            Layout::Float { size, endian } => {
+                if *size != 4 && *size != 8 {
+                    return Err(Error::invalid_data("unsupported float size").trace(None, offset));
+                }
+
+                let mut bytes = self.take(*size)?.to_vec();
+                if *endian == Endian::Big {
+                    bytes.reverse();
+                }
+
+                match *size {
+                    4 => (None, Some(f32::from_le_bytes(bytes[..].try_into().unwrap()).to_string())),
+                    _ => (None, Some(f64::from_le_bytes(bytes[..].try_into().unwrap()).to_string())),
+                }
+            }
+            Layout::Varint { bits, signed } => {
+                if !(1..=128).contains(bits) {
+                    return Err(Error::invalid_data("unsupported varint size").trace(None, offset));
+                }
+
+                let (val, read) =
+                    varint::decode_with_read_from(*bits, &self.buf[offset..]).map_err(|err| err.trace(None, offset))?;
+                self.read += read;
+
+                if *signed {
+                    (Some(val), Some(((val >> 1) as i128 ^ -((val & 1) as i128)).to_string()))
+                } else {
+                    (Some(val), Some(val.to_string()))
+                }
+            }
+            Layout::Array { elem, len } => {
+                self.elems(elem, *len)?;
+                (None, None)
+            }
+            Layout::Str { prefix } => {
+                let len = self.len(prefix)?;
+                let start = self.read;
+                match core::str::from_utf8(self.bytes(len)?) {
+                    Ok(str) => (None, Some(format!("{:?}", str))),
+                    Err(_) => return Err(Error::invalid_data("invalid utf-8").trace(None, start)),
+                }
+            }
+            Layout::Seq { prefix, elem } => {
+                let len = self.len(prefix)?;
+                if let Layout::Int { size: 1, signed: false, .. } = **elem {
+                    self.bytes(len)?;
+                    (None, Some(format!("{} byte(s)", len)))
+                } else {
+                    self.elems(elem, len)?;
+                    (None, None)
+                }
+            }
+            Layout::Map { prefix, key, value } => {
+                let len = self.len(prefix)?;
+                for idx in 0..len {
+                    self.nested(Step::Index(idx), |this| {
+                        this.nested(Step::Key, |this| this.walk(key))?;
+                        this.nested(Step::Value, |this| this.walk(value))
+                    })?;
+                }
+
+                (None, None)
+            }
+            Layout::Option(inner) => {
+                if self.nested(Step::Tag, |this| this.walk(&Layout::Bool))? == Some(1) {
+                    self.nested(Step::Some, |this| this.walk(inner))?;
+                }
+
+                (None, None)
+            }
+            Layout::Tuple(elems) => {
+                for (idx, elem) in elems.iter().enumerate() {
+                    self.nested(Step::Index(idx), |this| this.walk(elem))?;
+                }
+
+                (None, None)
+            }
+            Layout::Struct { fields, .. } => {
+                for field in fields {
+                    self.nested(Step::Field(field.name), |this| this.walk(&field.layout))?;
+                }
+
+                (None, None)
+            }
+            Layout::Enum { name, tag, prefix, variants } => {
+                let tag_idx = self.entries.len();
+                let val = self.nested(Step::Tag, |this| this.walk(tag))?.unwrap_or_default() as u64;
+                let end = match prefix {
+                    Some(prefix) => Some(self.len(prefix)? + self.read),
+                    None => None,
+                };
+
+                match (variants.iter().find(|variant| variant.tag == val), end) {
+                    (Some(variant), end) => {
+                        self.entries[tag_idx].value = Some(format!("{} ({})", val, variant.name));
+
+                        let buf = self.buf;
+                        self.buf = &buf[..end.unwrap_or(buf.len())];
+                        self.nested(Step::Variant(variant.name), |this| {
+                            for field in &variant.fields {
+                                this.nested(Step::Field(field.name), |this| this.walk(&field.layout))?;
+                            }
+
+                            Ok(None)
+                        })?;
+                        self.buf = buf;
+
+                        // Fields added by a newer version of the variant.
+                        if let Some(end) = end.filter(|end| *end > self.read) {
+                            self.bytes(end - self.read)?;
+                        }
+                    }
+                    (None, Some(end)) => {
+                        self.entries[tag_idx].value = Some(format!("{} (unknown)", val));
+                        self.bytes(end - self.read)?;
+                    }
+                    (None, None) => return Err(Error::invalid_tag(name, val).trace(None, offset)),
+                }
+
+                (None, None)
+            }
+        };
+
+        self.entries[idx].len = self.read - offset;
+        self.entries[idx].value = value;
+
+        Ok(int)
+    }
+
+    fn nested<F>(&mut self, step: Step, f: F) -> Result<Option<u128>, Error>
+    where
+        F: FnOnce(&mut Self) -> Result<Option<u128>, Error>,
+    {
+        self.path.push(step);
+        let val = f(self)?;
+        self.path.pop();
+
+        Ok(val)
+    }
+
+    fn len(&mut self, prefix: &Layout) -> Result<usize, Error> {
+        let offset = self.read;
+        let len = self.nested(Step::Prefix, |this| this.walk(prefix))?.unwrap_or_default();
+        if len > (self.buf.len() - self.read) as u128 {
+            Err(Error::unexpected_eof((len - (self.buf.len() - self.read) as u128) as usize).trace(None, offset))
+        } else {
+            Ok(len as usize)
+        }
+    }
+
+    // Raw bytes of a string or byte sequence, after their length prefix.
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let mut path = self.path.clone();
+        path.push(Step::Elem);
+        self.entries.push(Entry { path, offset: self.read, len, ty: "bytes".to_string(), value: None });
+
+        self.take(len)
+    }
+
+    fn elems(&mut self, elem: &Layout, len: usize) -> Result<(), Error> {
+        for idx in 0..len {
+            self.nested(Step::Index(idx), |this| this.walk(elem))?;
+        }
+
+        Ok(())
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                      struct Registry                                       │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Maps type names to layouts so that a buffer can be dumped against a type chosen at runtime (e.g.
+// by the `f0rm47-dump` binary).
+#[derive(Clone, Debug, Default)]
+pub struct Registry {
+    layouts: BTreeMap<String, Layout>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry::default()
+    }
+
+    pub fn register<T: Schema + ?Sized>(&mut self, name: &str) -> &mut Self {
+        self.layouts.insert(name.to_string(), T::schema());
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Layout> {
+        self.layouts.get(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.layouts.keys().map(String::as_str)
+    }
+
+    pub fn dump<'a>(&self, name: &str, buf: &'a [u8]) -> Option<Dump<'a>> {
+        Some(dump_as(self.get(name)?, buf))
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          fn cli()                                          │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Entry point of `f0rm47-dump`, exposed so that applications can build their own dumper with their
+// own types registered:
+//
+//     f0rm47-dump [--hex] <type> <file or ->
+//     f0rm47-dump --list
+#[cfg(feature = "std")]
+pub fn cli(registry: &Registry) -> ExitCode {
+    let mut args = env::args().skip(1).collect::<Vec<_>>();
+    let is_hex = args.first().map(String::as_str) == Some("--hex");
+    if is_hex {
+        args.remove(0);
+    }
+
+    let (name, path) = match &args[..] {
+        [list] if list == "--list" => {
+            registry.names().for_each(|name| println!("{}", name));
+            return ExitCode::SUCCESS;
+        }
+        [name, path] => (name, path),
+        _ => {
+            eprintln!("usage: f0rm47-dump [--hex] <type> <file or ->\n       f0rm47-dump --list");
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut buf = Vec::new();
+    let read =
+        if path == "-" { io::stdin().read_to_end(&mut buf).map(drop) } else { fs::read(path).map(|read| buf = read) };
+    if let Err(err) = read {
+        eprintln!("{}: {}", path, err);
+        return ExitCode::from(2);
+    }
+
+    if is_hex {
+        match parse_hex(&buf) {
+            Some(bytes) => buf = bytes,
+            None => {
+                eprintln!("{}: invalid hex", path);
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    match registry.dump(name, &buf) {
+        Some(dump) => {
+            print!("{}", dump);
+            if dump.is_ok() {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        None => {
+            eprintln!("unknown type `{}` (see --list)", name);
+            ExitCode::from(2)
+        }
+    }
+}
+
+// Parses pairs of hex digits, ignoring whitespace.
+#[cfg(feature = "std")]
+fn parse_hex(text: &[u8]) -> Option<Vec<u8>> {
+    let digits = text
+        .iter()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .map(|byte| (*byte as char).to_digit(16))
+        .collect::<Option<Vec<_>>>()?;
+
+    if digits.len() % 2 != 0 {
+        return None;
+    }
+
+    Some(digits.chunks(2).map(|pair| (pair[0] * 16 + pair[1]) as u8).collect())
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        impl Display                                        │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+const WIDTH: usize = 16;
+const HEX: usize = WIDTH * 3 - 1;
+
+impl Display for Dump<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for (idx, entry) in self.entries.iter().enumerate() {
+            let label = match entry.path.last() {
+                Some(step) => label(step),
+                None => "<root>".to_string(),
+            };
+
+            let mut annotation = format!("{:indent$}{}: {}", "", label, entry.ty, indent = entry.path.len() * 2);
+            if let Some(value) = &entry.value {
+                annotation = format!("{} = {}", annotation, value);
+            }
+
+            // Entries are stored depth-first, so an entry has children if the next one is nested
+            // under it. Their bytes are then printed by the children themselves.
+            let next = self.entries.get(idx + 1);
+            if next.is_some_and(|next| next.path.len() > entry.path.len() && next.path.starts_with(&entry.path)) {
+                writeln!(
+                    f,
+                    "{:08x}  {:width$}  {} ({} byte(s))",
+                    entry.offset,
+                    "",
+                    annotation,
+                    entry.len,
+                    width = HEX
+                )?;
+                continue;
+            }
+
+            let mut chunks = self.buf[entry.offset..entry.offset + entry.len].chunks(WIDTH);
+            let first = hex(chunks.next().unwrap_or(&[]));
+            writeln!(f, "{:08x}  {:width$}  {}", entry.offset, first, annotation, width = HEX)?;
+            for (idx, chunk) in chunks.enumerate() {
+                writeln!(f, "{:08x}  {}", entry.offset + (idx + 1) * WIDTH, hex(chunk))?;
+            }
+        }
+
+        if let Some((path, err)) = &self.error {
+            write!(f, "{:08x}  error at ", err.offset())?;
+            if path.is_empty() {
+                f.write_str("<root>")?;
+            }
+
+            for step in path {
+                write!(f, "{}", step)?;
+            }
+
+            writeln!(f, ": {}", err.kind())?;
+        } else if self.read < self.buf.len() {
+            let trailing = &self.buf[self.read..];
+            writeln!(f, "{:08x}  {} trailing byte(s)", self.read, trailing.len())?;
+        }
+
+        Ok(())
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<Vec<_>>().join(" ")
+}
+
+fn label(step: &Step) -> String {
+    match step {
+        Step::Field(name) | Step::Variant(name) => name.to_string(),
+        Step::Index(idx) => format!("[{}]", idx),
+        Step::Prefix => "<len>".to_string(),
+        Step::Tag => "<tag>".to_string(),
+        Step::Elem => "<data>".to_string(),
+        Step::Key => "<key>".to_string(),
+        Step::Value => "<value>".to_string(),
+        Step::Some => "<some>".to_string(),
+    }
+}
+
+fn ty(layout: &Layout) -> String {
+    match layout {
+        Layout::Bool => "bool".to_string(),
+        Layout::Char => "char".to_string(),
+        Layout::Int { size, signed, endian } => {
+            format!("{}{}{}", if *signed { "i" } else { "u" }, size * 8, if *endian == Endian::Big { "be" } else { "" })
+        }
+        Layout::Float { size, endian } => format!("f{}{}", size * 8, if *endian == Endian::Big { "be" } else { "" }),
+        Layout::Varint { bits, signed: false } => format!("varint<u{}>", bits),
+        Layout::Varint { bits, signed: true } => format!("zigzag<i{}>", bits),
+        Layout::Array { elem, len } => format!("[{}; {}]", ty(elem), len),
+        Layout::Str { .. } => "str".to_string(),
+        Layout::Seq { elem, .. } => format!("[{}]", ty(elem)),
+        Layout::Map { key, value, .. } => format!("map<{}, {}>", ty(key), ty(value)),
+        Layout::Option(inner) => format!("Option<{}>", ty(inner)),
+        Layout::Tuple(elems) => format!("({})", elems.iter().map(ty).collect::<Vec<_>>().join(", ")),
+        Layout::Struct { name, .. } | Layout::Enum { name, .. } => name.to_string(),
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(test)]
+#[test]
+fn hexdump() {
+    use crate::{Encode, ErrorKind};
+
+    let encoded = (42u32, Some("héllo"), [true, false], -1i16).encode().unwrap();
+    let traced = dump::<(u32, Option<&str>, [bool; 2], i16)>(&encoded);
+    assert!(traced.is_ok());
+    assert_eq!(traced.read(), encoded.len());
+
+    let entry = traced.entries().iter().find(|entry| entry.path == [Step::Index(1), Step::Some]).unwrap();
+    assert_eq!((entry.offset, entry.len, &entry.ty[..]), (5, 8, "str"));
+    assert_eq!(entry.value.as_deref(), Some("\"héllo\""));
+
+    let rendered = traced.to_string();
+    assert!(rendered.contains("00000000  2a 00 00 00"));
+    assert!(rendered.contains("  [3]: i16 = -1"));
+
+    let traced = dump::<(u32, Option<&str>, [bool; 2], i16)>(&encoded[..encoded.len() - 4]);
+    let (path, err) = traced.error().unwrap();
+    assert_eq!(path, [Step::Index(2), Step::Index(0)]);
+    assert!(matches!(err.kind(), ErrorKind::UnexpectedEof { needed: 1 }));
+    assert_eq!(err.offset(), encoded.len() - 4);
+    assert!(traced.to_string().ends_with("error at .2.0: unexpected eof, 1 more byte(s) needed\n"));
+
+    for layout in [
+        Layout::Int { size: 0, signed: true, endian: Endian::Little },
+        Layout::Int { size: 17, signed: false, endian: Endian::Little },
+        Layout::Float { size: 2, endian: Endian::Big },
+        Layout::Varint { bits: 0, signed: false },
+        Layout::Varint { bits: 200, signed: false },
+    ] {
+        let traced = dump_as(&layout, &[0; 32]);
+        assert!(matches!(traced.error().unwrap().1.kind(), ErrorKind::InvalidData { .. }));
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+#[test]
+fn hex_input() {
+    assert_eq!(parse_hex(b"2a 00\nfF").unwrap(), [0x2a, 0x00, 0xff]);
+    assert!(parse_hex(b"2a0").is_none());
+    assert!(parse_hex(b"+1").is_none());
+}
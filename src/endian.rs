@@ -0,0 +1,133 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use crate::borrow::owned;
+use crate::io::{Read, Write};
+use crate::{Decode, Encode, Error};
+use core::mem;
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                    struct BigEndian<T>                                     │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct BigEndian<T>(pub T);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                           Macros                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+macro_rules! primitive {
+    ($primitive:ty) => {
+        impl Encode for BigEndian<$primitive> {
+            type Error = Error;
+
+            fn fast_size(&self) -> usize {
+                mem::size_of::<$primitive>()
+            }
+
+            fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+                Ok(writer.write_all(&self.0.to_be_bytes())?)
+            }
+        }
+
+        impl Decode for BigEndian<$primitive> {
+            fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+                let bytes = <[u8; mem::size_of::<$primitive>()]>::decode_from(reader)?;
+                Ok((BigEndian(<$primitive>::from_be_bytes(bytes)), mem::size_of::<$primitive>()))
+            }
+        }
+    };
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                       primitive!(..)                                       │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+primitive!(u8);
+primitive!(u16);
+primitive!(u32);
+primitive!(u64);
+primitive!(u128);
+
+primitive!(i8);
+primitive!(i16);
+primitive!(i32);
+primitive!(i64);
+primitive!(i128);
+
+primitive!(f32);
+primitive!(f64);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                       impl {En,De}code for BigEndian<{usize,isize}>                        │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Encode for BigEndian<usize> {
+    type Error = Error;
+
+    fn fast_size(&self) -> usize {
+        BigEndian(self.0 as u64).fast_size()
+    }
+
+    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+        BigEndian(self.0 as u64).encode_into(writer)
+    }
+}
+
+impl Encode for BigEndian<isize> {
+    type Error = Error;
+
+    fn fast_size(&self) -> usize {
+        BigEndian(self.0 as i64).fast_size()
+    }
+
+    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+        BigEndian(self.0 as i64).encode_into(writer)
+    }
+}
+
+impl Decode for BigEndian<usize> {
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+        let (BigEndian(num), len) = BigEndian::<u64>::decode_with_read_from(reader)?;
+        Ok((BigEndian(num as usize), len))
+    }
+}
+
+impl Decode for BigEndian<isize> {
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+        let (BigEndian(num), len) = BigEndian::<i64>::decode_with_read_from(reader)?;
+        Ok((BigEndian(num as isize), len))
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                         owned!(..)                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+owned!(BigEndian<u8>, BigEndian<u16>, BigEndian<u32>, BigEndian<u64>, BigEndian<u128>, BigEndian<usize>);
+owned!(BigEndian<i8>, BigEndian<i16>, BigEndian<i32>, BigEndian<i64>, BigEndian<i128>, BigEndian<isize>);
+owned!(BigEndian<f32>, BigEndian<f64>);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(test)]
+#[test]
+fn header() {
+    let header = (BigEndian(0x0102u16), [BigEndian(0x0304u16); 2], BigEndian(-2i32));
+    let encoded = header.encode().unwrap();
+    assert_eq!(encoded, [0x01, 0x02, 0x03, 0x04, 0x03, 0x04, 0xff, 0xff, 0xff, 0xfe]);
+    assert_eq!(header.fast_size(), encoded.len());
+    assert_eq!(<(BigEndian<u16>, [BigEndian<u16>; 2], BigEndian<i32>)>::decode(&encoded).unwrap(), header);
+}
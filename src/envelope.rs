@@ -0,0 +1,247 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use crate::io::{self, Read, Write};
+use crate::limits::{self, Depth};
+use crate::prefix::{Prefix, U32};
+use crate::schema::{Field, Layout, Schema};
+use crate::{Decode, Encode, Error, Incomplete, Trace};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                      trait Versioned                                       │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+pub trait Versioned: Decode {
+    const VERSION: u16;
+
+    // Called with the body of an envelope written with an older version. The default
+    // implementation decodes it as the current version, which only works if the fields added
+    // since then are trailing `#[f0rm47(default)]` fields.
+    fn upgrade(from_version: u16, body: &[u8]) -> Result<Self, Self::Error>
+    where
+        Self::Error: From<Error>,
+    {
+        let _ = from_version;
+        decode_exact(body)
+    }
+}
+
+fn decode_exact<T: Decode>(body: &[u8]) -> Result<T, T::Error>
+where
+    T::Error: From<Error>,
+{
+    let (val, read) = T::decode_body(body)?;
+    if read != body.len() {
+        return Err(Error::invalid_data("trailing bytes in envelope").into());
+    }
+
+    Ok(val)
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                    fn decode_default()                                     │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Used by derived types to decode their `#[f0rm47(default)]` fields: `T::default()` is only
+// returned if the input is `bounded` (see `Decode::decode_body()`) and ended right before the
+// field, not if it ended partway through it.
+pub fn decode_default<T, E, R>(reader: R, bounded: bool) -> Result<(T, usize), E>
+where
+    T: Decode + Default,
+    E: From<T::Error> + Incomplete,
+    R: Read,
+{
+    let mut reader = Counted { inner: reader, read: 0 };
+    match T::decode_with_read_from(&mut reader) {
+        Ok(decoded) => Ok(decoded),
+        Err(err) => {
+            let err = E::from(err);
+            if bounded && reader.read == 0 && err.needed().is_some() {
+                Ok((T::default(), 0))
+            } else {
+                Err(err)
+            }
+        }
+    }
+}
+
+struct Counted<R> {
+    inner: R,
+    read: usize,
+}
+
+impl<R: Read> Read for Counted<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.read += read;
+
+        Ok(read)
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                     struct Envelope<T>                                     │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Encodes `T::VERSION` followed by the `U32`-prefixed encoding of `T`. Envelopes written with a
+// newer version are decoded as the current one, skipping whatever trailing bytes the newer
+// version added, while envelopes written with an older one go through `T::upgrade()`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Envelope<T>(pub T);
+
+impl<T> Envelope<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Envelope<T> {
+    fn from(val: T) -> Self {
+        Envelope(val)
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                              impl {En,De}code for Envelope<T>                              │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl<T: Versioned> Encode for Envelope<T>
+where
+    T::Error: From<Error>,
+{
+    type Error = T::Error;
+
+    fn size(&self) -> Result<usize, Self::Error> {
+        let size = self.0.size()?;
+        Ok(T::VERSION.fast_size() + U32::fast_size(size) + size)
+    }
+
+    fn fast_size(&self) -> usize {
+        let size = self.0.fast_size();
+        T::VERSION.fast_size() + U32::fast_size(size) + size
+    }
+
+    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        T::VERSION.encode_into(&mut writer)?;
+        U32::encode_into(self.0.size()?, &mut writer)?;
+        self.0.encode_into(writer)
+    }
+}
+
+impl<T: Versioned> Decode for Envelope<T>
+where
+    T::Error: From<Error> + Trace,
+{
+    fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
+        let _depth = Depth::enter()?;
+        let (version, read1) = u16::decode_with_read_from(&mut reader)?;
+        let (len, read2) = U32::decode_with_read_from(&mut reader).map_err(|err| err.trace(None, read1))?;
+        let header = read1 + read2;
+
+        limits::len(len)?;
+        limits::alloc::<u8>(len)?;
+        let body = limits::read_vec(reader, len).map_err(|err| err.trace(None, header))?;
+
+        let val = match version.cmp(&T::VERSION) {
+            Ordering::Less => T::upgrade(version, &body),
+            Ordering::Equal => decode_exact(&body),
+            Ordering::Greater => T::decode_body(&body).map(|(val, _)| val),
+        };
+
+        Ok((Envelope(val.map_err(|err| err.trace(None, header))?), header + len))
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                impl Schema for Envelope<T>                                 │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// The body is opaque: its layout is allowed to change between versions.
+impl<T> Schema for Envelope<T> {
+    fn schema() -> Layout {
+        Layout::Struct {
+            name: "Envelope",
+            fields: Vec::from([
+                Field { name: "version", layout: u16::schema() },
+                Field {
+                    name: "body",
+                    layout: Layout::Seq { prefix: Box::new(U32::schema()), elem: Box::new(u8::schema()) },
+                },
+            ]),
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(test)]
+#[test]
+fn versions() {
+    use crate::ErrorKind;
+
+    #[derive(Debug, PartialEq)]
+    struct Message {
+        id: u8,
+        flags: u16,
+    }
+
+    impl Encode for Message {
+        type Error = Error;
+
+        fn fast_size(&self) -> usize {
+            self.id.fast_size() + self.flags.fast_size()
+        }
+
+        fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+            self.id.encode_into(&mut writer)?;
+            self.flags.encode_into(writer)
+        }
+    }
+
+    impl Decode for Message {
+        fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+            let ((id, flags), read) = <(u8, u16)>::decode_with_read_from(reader)?;
+            Ok((Message { id, flags }, read))
+        }
+    }
+
+    impl Versioned for Message {
+        const VERSION: u16 = 2;
+
+        // Version 1 had no flags, and version 0 stored the id as a `u16`.
+        fn upgrade(from_version: u16, body: &[u8]) -> Result<Self, Self::Error> {
+            match from_version {
+                0 => Ok(Message { id: u16::decode(body)? as u8, flags: 0 }),
+                _ => Ok(Message { id: u8::decode(body)?, flags: 0 }),
+            }
+        }
+    }
+
+    let encoded = Envelope(Message { id: 1, flags: 2 }).encode().unwrap();
+    assert_eq!(encoded, [2, 0, 3, 0, 0, 0, 1, 2, 0]);
+    assert_eq!(Envelope::<Message>::decode(&encoded).unwrap().0, Message { id: 1, flags: 2 });
+
+    assert_eq!(Envelope::<Message>::decode(&[0, 0, 2, 0, 0, 0, 3, 0]).unwrap().0, Message { id: 3, flags: 0 });
+    assert_eq!(Envelope::<Message>::decode(&[1, 0, 1, 0, 0, 0, 4]).unwrap().0, Message { id: 4, flags: 0 });
+
+    let newer = [3, 0, 5, 0, 0, 0, 5, 6, 0, 7, 8];
+    assert_eq!(Envelope::<Message>::decode_with_read(&newer).unwrap(), (Envelope(Message { id: 5, flags: 6 }), 11));
+
+    let err = Envelope::<Message>::decode(&[2, 0, 4, 0, 0, 0, 1, 2, 0, 3]).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidData { .. }));
+    assert_eq!(err.offset(), 6);
+}
@@ -0,0 +1,280 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use crate::io::{self, Read};
+use crate::limits::Limit;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::error;
+use core::fmt::{self, Display, Formatter};
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        struct Error                                        │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    // Innermost segment first.
+    path: Vec<Segment>,
+    offset: usize,
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    UnexpectedEof { needed: usize },
+    LengthOverflow { limit: u64, actual: u64 },
+    InvalidTag { ty: &'static str, tag: u64 },
+    InvalidData { context: &'static str },
+    LimitExceeded { limit: Limit, max: usize },
+    Unsupported { context: &'static str },
+    Custom(String),
+    Io(io::Error),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Segment {
+    Field(&'static str),
+    Index(usize),
+    Key(usize),
+    Value(usize),
+}
+
+impl Error {
+    pub fn new(kind: ErrorKind) -> Self {
+        Error { kind, path: Vec::new(), offset: 0 }
+    }
+
+    pub fn unexpected_eof(needed: usize) -> Self {
+        Error::new(ErrorKind::UnexpectedEof { needed })
+    }
+
+    pub fn length_overflow(limit: u64, actual: u64) -> Self {
+        Error::new(ErrorKind::LengthOverflow { limit, actual })
+    }
+
+    pub fn invalid_tag(ty: &'static str, tag: u64) -> Self {
+        Error::new(ErrorKind::InvalidTag { ty, tag })
+    }
+
+    pub fn invalid_data(context: &'static str) -> Self {
+        Error::new(ErrorKind::InvalidData { context })
+    }
+
+    // Keeps the path and offset of the error.
+    #[cfg(feature = "serde")]
+    pub(crate) fn with_kind(mut self, kind: ErrorKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    pub fn into_kind(self) -> ErrorKind {
+        self.kind
+    }
+
+    pub fn path(&self) -> impl Iterator<Item = &Segment> {
+        self.path.iter().rev()
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        trait Trace                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Called by composite types when one of their parts fails to decode, with the part's segment (if
+// any) and the number of bytes read before it. The default implementation discards both.
+pub trait Trace: Sized {
+    fn trace(self, segment: Option<Segment>, offset: usize) -> Self {
+        let _ = (segment, offset);
+        self
+    }
+}
+
+impl Trace for Error {
+    fn trace(mut self, segment: Option<Segment>, offset: usize) -> Self {
+        self.path.extend(segment);
+        self.offset += offset;
+        self
+    }
+}
+
+impl Trace for io::Error {}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                      trait Incomplete                                      │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Implemented by error types which can tell whether decoding failed only because the input ended
+// early, and how many more bytes were needed (at least) when it did.
+pub trait Incomplete {
+    fn needed(&self) -> Option<usize>;
+}
+
+impl Incomplete for Error {
+    fn needed(&self) -> Option<usize> {
+        if let ErrorKind::UnexpectedEof { needed } = self.kind {
+            Some(needed.max(1))
+        } else {
+            None
+        }
+    }
+}
+
+impl Incomplete for io::Error {
+    fn needed(&self) -> Option<usize> {
+        if self.kind() == io::ErrorKind::UnexpectedEof {
+            Some(1)
+        } else {
+            None
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                      fn read_exact()                                       │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+pub(crate) fn read_exact<R: Read>(mut reader: R, mut buf: &mut [u8]) -> Result<(), Error> {
+    while !buf.is_empty() {
+        match reader.read(buf) {
+            Ok(0) => return Err(Error::unexpected_eof(buf.len())),
+            Ok(read) => buf = &mut buf[read..],
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => (),
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    Ok(())
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        impl Display                                        │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+
+        if !self.path.is_empty() {
+            f.write_str(" at ")?;
+            for segment in self.path() {
+                write!(f, "{}", segment)?;
+            }
+        }
+
+        write!(f, " (offset {})", self.offset)
+    }
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedEof { needed } => write!(f, "unexpected eof, {} more byte(s) needed", needed),
+            ErrorKind::LengthOverflow { limit, actual } => write!(f, "length {} exceeds limit {}", actual, limit),
+            ErrorKind::InvalidTag { ty, tag } => write!(f, "invalid tag {} for {}", tag, ty),
+            ErrorKind::InvalidData { context } => write!(f, "invalid data: {}", context),
+            ErrorKind::LimitExceeded { limit, max } => write!(f, "decode limit exceeded: {} = {}", limit, max),
+            ErrorKind::Unsupported { context } => write!(f, "unsupported: {}", context),
+            ErrorKind::Custom(message) => f.write_str(message),
+            ErrorKind::Io(err) => write!(f, "io error: {}", err),
+        }
+    }
+}
+
+impl Display for Segment {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Segment::Field(name) => write!(f, ".{}", name),
+            Segment::Index(idx) => write!(f, "[{}]", idx),
+            Segment::Key(idx) => write!(f, "[{}].key", idx),
+            Segment::Value(idx) => write!(f, "[{}].value", idx),
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                     impl error::Error                                      │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        if let ErrorKind::Io(err) = &self.kind {
+            Some(err)
+        } else {
+            None
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                         impl From                                          │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Error::new(kind)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        if err.get_ref().is_some_and(|inner| inner.is::<Error>()) {
+            *err.into_inner().unwrap().downcast().unwrap()
+        } else {
+            Error::new(ErrorKind::Io(err))
+        }
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        let kind = match err.kind {
+            ErrorKind::Io(err) => return err,
+            ErrorKind::UnexpectedEof { .. } => io::ErrorKind::UnexpectedEof,
+            ErrorKind::LengthOverflow { .. } | ErrorKind::LimitExceeded { .. } | ErrorKind::Unsupported { .. } => {
+                io::ErrorKind::InvalidInput
+            }
+            _ => io::ErrorKind::InvalidData,
+        };
+
+        io::Error::new(kind, err)
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(all(test, feature = "collections"))]
+#[test]
+fn breadcrumbs() {
+    use crate::Decode;
+
+    let err = <(u8, Vec<bool>)>::decode(&[0, 3, 0, 1, 0, 2]).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidTag { ty: "bool", tag: 2 }));
+    assert_eq!(err.path().copied().collect::<Vec<_>>(), [Segment::Index(1), Segment::Index(2)]);
+    assert_eq!(err.offset(), 5);
+    assert_eq!(err.to_string(), "invalid tag 2 for bool at [1][2] (offset 5)");
+
+    let err = <(u8, u32)>::decode_from(&[0, 1, 2][..]).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::UnexpectedEof { needed: 2 }));
+    assert_eq!(err.offset(), 1);
+}
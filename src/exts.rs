@@ -10,6 +10,8 @@
  * │                                          Imports                                           │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
+mod time;
+
 use cfg_if::cfg_if;
 
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
@@ -39,6 +41,12 @@ cfg_if! {
     }
 }
 
+cfg_if! {
+    if #[cfg(all(feature = "ed25519", feature = "secrets"))] {
+        mod ed25519_secret;
+    }
+}
+
 cfg_if! {
     if #[cfg(feature = "net")] {
         mod net;
@@ -62,3 +70,9 @@ cfg_if! {
         mod x25519;
     }
 }
+
+cfg_if! {
+    if #[cfg(all(feature = "x25519", feature = "secrets"))] {
+        mod x25519_secret;
+    }
+}
@@ -10,16 +10,24 @@
  * │                                          Imports                                           │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
-use crate::{Decode, Encode};
-use chrono::{Date, DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
-use std::io::{self, Read, Write};
+use crate::borrow::{owned, DecodeBorrow};
+use crate::io::{Read, Write};
+use crate::schema::{Field, Layout, Schema};
+use crate::{Decode, Encode, Error, Trace};
+use alloc::vec::Vec;
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc,
+};
+
+#[allow(deprecated)]
+use chrono::Date;
 
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
- * │                             impl {En,De}code for DateTime<Utc>                             │ *
+ * │                             impl {En,De}code for DateTime<Tz>                              │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
 impl Encode for DateTime<Utc> {
-    type Error = io::Error;
+    type Error = Error;
 
     fn fast_size(&self) -> usize {
         self.naive_utc().fast_size()
@@ -30,10 +38,57 @@ impl Encode for DateTime<Utc> {
     }
 }
 
+// Encoded as the UTC date and time followed by the offset (in seconds east of UTC) so that the
+// date and time stay comparable across offsets.
+impl Encode for DateTime<FixedOffset> {
+    type Error = Error;
+
+    fn fast_size(&self) -> usize {
+        self.naive_utc().fast_size() + self.offset().local_minus_utc().fast_size()
+    }
+
+    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        self.naive_utc().encode_into(&mut writer)?;
+        self.offset().local_minus_utc().encode_into(writer)
+    }
+}
+
+// Encoded like `DateTime<FixedOffset>`, with the offset in effect at that time.
+impl Encode for DateTime<Local> {
+    type Error = Error;
+
+    fn fast_size(&self) -> usize {
+        self.fixed_offset().fast_size()
+    }
+
+    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+        self.fixed_offset().encode_into(writer)
+    }
+}
+
 impl Decode for DateTime<Utc> {
     fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
         let (datetime, len) = NaiveDateTime::decode_with_read_from(reader)?;
-        Ok((Self::from_utc(datetime, Utc), len))
+        Ok((Utc.from_utc_datetime(&datetime), len))
+    }
+}
+
+impl Decode for DateTime<FixedOffset> {
+    fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
+        let (datetime, read1) = NaiveDateTime::decode_with_read_from(&mut reader)?;
+        let (secs, read2) = i32::decode_with_read_from(reader).map_err(|err| err.trace(None, read1))?;
+
+        match FixedOffset::east_opt(secs) {
+            Some(offset) => Ok((offset.from_utc_datetime(&datetime), read1 + read2)),
+            None => Err(Error::invalid_data("invalid utc offset").trace(None, read1)),
+        }
+    }
+}
+
+impl Decode for DateTime<Local> {
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+        let (datetime, len) = DateTime::<FixedOffset>::decode_with_read_from(reader)?;
+        Ok((datetime.with_timezone(&Local), len))
     }
 }
 
@@ -42,7 +97,7 @@ impl Decode for DateTime<Utc> {
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
 impl Encode for NaiveDateTime {
-    type Error = io::Error;
+    type Error = Error;
 
     fn fast_size(&self) -> usize {
         self.date().fast_size() + self.time().fast_size()
@@ -57,7 +112,7 @@ impl Encode for NaiveDateTime {
 impl Decode for NaiveDateTime {
     fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
         let (date, read1) = NaiveDate::decode_with_read_from(&mut reader)?;
-        let (time, read2) = NaiveTime::decode_with_read_from(&mut reader)?;
+        let (time, read2) = NaiveTime::decode_with_read_from(&mut reader).map_err(|err| err.trace(None, read1))?;
         Ok((Self::new(date, time), read1 + read2))
     }
 }
@@ -66,8 +121,9 @@ impl Decode for NaiveDateTime {
  * │                               impl {En,De}code for Date<Utc>                               │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
+#[allow(deprecated)]
 impl Encode for Date<Utc> {
-    type Error = io::Error;
+    type Error = Error;
 
     fn fast_size(&self) -> usize {
         self.naive_utc().fast_size()
@@ -78,6 +134,7 @@ impl Encode for Date<Utc> {
     }
 }
 
+#[allow(deprecated)]
 impl Decode for Date<Utc> {
     fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
         let (date, len) = NaiveDate::decode_with_read_from(reader)?;
@@ -85,12 +142,20 @@ impl Decode for Date<Utc> {
     }
 }
 
+// `owned!()` can't be used without triggering the deprecation warning.
+#[allow(deprecated)]
+impl<'de> DecodeBorrow<'de> for Date<Utc> {
+    fn decode_borrow_with_read(buf: &'de [u8]) -> Result<(Self, usize), Self::Error> {
+        Self::decode_with_read(buf)
+    }
+}
+
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
  * │                               impl {En,De}code for NaiveDate                               │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
 impl Encode for NaiveDate {
-    type Error = io::Error;
+    type Error = Error;
 
     fn fast_size(&self) -> usize {
         self.num_days_from_ce().fast_size()
@@ -104,39 +169,201 @@ impl Encode for NaiveDate {
 impl Decode for NaiveDate {
     fn decode_with_read(buf: &[u8]) -> Result<(Self, usize), Self::Error> {
         let (days, len) = i32::decode_with_read(buf)?;
-        Ok((Self::from_num_days_from_ce(days), len))
+        Ok((date(days)?, len))
     }
 
     fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
         let (days, len) = i32::decode_with_read_from(reader)?;
-        Ok((Self::from_num_days_from_ce(days), len))
+        Ok((date(days)?, len))
     }
 }
 
+fn date(days: i32) -> Result<NaiveDate, Error> {
+    NaiveDate::from_num_days_from_ce_opt(days).ok_or_else(|| Error::invalid_data("invalid date"))
+}
+
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
  * │                               impl {En,De}code for NaiveTime                               │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
+// Encoded as the number of seconds since midnight followed by the number of nanoseconds, which
+// goes over 1,000,000,000 during leap seconds.
 impl Encode for NaiveTime {
-    type Error = io::Error;
+    type Error = Error;
 
     fn fast_size(&self) -> usize {
-        self.num_seconds_from_midnight().fast_size()
+        self.num_seconds_from_midnight().fast_size() + self.nanosecond().fast_size()
     }
 
-    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
-        self.num_seconds_from_midnight().encode_into(writer)
+    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        self.num_seconds_from_midnight().encode_into(&mut writer)?;
+        self.nanosecond().encode_into(writer)
     }
 }
 
 impl Decode for NaiveTime {
     fn decode_with_read(buf: &[u8]) -> Result<(Self, usize), Self::Error> {
-        let (secs, len) = u32::decode_with_read(buf)?;
-        Ok((Self::from_num_seconds_from_midnight(secs, 0), len))
+        let ((secs, nanos), len) = <(u32, u32)>::decode_with_read(buf)?;
+        Ok((time(secs, nanos)?, len))
     }
 
     fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
-        let (secs, len) = u32::decode_with_read_from(reader)?;
-        Ok((Self::from_num_seconds_from_midnight(secs, 0), len))
+        let ((secs, nanos), len) = <(u32, u32)>::decode_with_read_from(reader)?;
+        Ok((time(secs, nanos)?, len))
+    }
+}
+
+fn time(secs: u32, nanos: u32) -> Result<NaiveTime, Error> {
+    NaiveTime::from_num_seconds_from_midnight_opt(secs, nanos).ok_or_else(|| Error::invalid_data("invalid time"))
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                               impl {En,De}code for Duration                                │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Encoded as a number of seconds followed by a number of nanoseconds, which is always positive (so
+// that -1.5s is encoded as -2s and 500,000,000ns).
+impl Encode for Duration {
+    type Error = Error;
+
+    fn fast_size(&self) -> usize {
+        0i64.fast_size() + 0u32.fast_size()
+    }
+
+    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        let (secs, nanos) = match self.subsec_nanos() {
+            nanos if nanos < 0 => (self.num_seconds() - 1, (nanos + 1_000_000_000) as u32),
+            nanos => (self.num_seconds(), nanos as u32),
+        };
+
+        secs.encode_into(&mut writer)?;
+        nanos.encode_into(writer)
     }
 }
+
+impl Decode for Duration {
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+        let ((secs, nanos), len) = <(i64, u32)>::decode_with_read_from(reader)?;
+        match Duration::new(secs, nanos) {
+            Some(duration) => Ok((duration, len)),
+            None => Err(Error::invalid_data("invalid duration")),
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                     impl Schema for ..                                     │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Schema for DateTime<Utc> {
+    fn schema() -> Layout {
+        NaiveDateTime::schema()
+    }
+}
+
+impl Schema for DateTime<FixedOffset> {
+    fn schema() -> Layout {
+        Layout::Struct {
+            name: "DateTime",
+            fields: Vec::from([
+                Field { name: "datetime", layout: NaiveDateTime::schema() },
+                Field { name: "offset", layout: i32::schema() },
+            ]),
+        }
+    }
+}
+
+impl Schema for DateTime<Local> {
+    fn schema() -> Layout {
+        DateTime::<FixedOffset>::schema()
+    }
+}
+
+impl Schema for NaiveDateTime {
+    fn schema() -> Layout {
+        Layout::Struct {
+            name: "NaiveDateTime",
+            fields: Vec::from([
+                Field { name: "date", layout: NaiveDate::schema() },
+                Field { name: "time", layout: NaiveTime::schema() },
+            ]),
+        }
+    }
+}
+
+#[allow(deprecated)]
+impl Schema for Date<Utc> {
+    fn schema() -> Layout {
+        NaiveDate::schema()
+    }
+}
+
+impl Schema for NaiveDate {
+    fn schema() -> Layout {
+        Layout::Struct { name: "NaiveDate", fields: Vec::from([Field { name: "days_from_ce", layout: i32::schema() }]) }
+    }
+}
+
+impl Schema for NaiveTime {
+    fn schema() -> Layout {
+        Layout::Struct {
+            name: "NaiveTime",
+            fields: Vec::from([
+                Field { name: "secs_from_midnight", layout: u32::schema() },
+                Field { name: "nanos", layout: u32::schema() },
+            ]),
+        }
+    }
+}
+
+impl Schema for Duration {
+    fn schema() -> Layout {
+        Layout::Struct {
+            name: "Duration",
+            fields: Vec::from([
+                Field { name: "secs", layout: i64::schema() },
+                Field { name: "nanos", layout: u32::schema() },
+            ]),
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                         owned!(..)                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+owned!(DateTime<Utc>, DateTime<FixedOffset>, DateTime<Local>, NaiveDateTime, NaiveDate, NaiveTime, Duration);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(test)]
+#[test]
+fn checked() {
+    use crate::ErrorKind;
+
+    let time = NaiveTime::from_hms_nano_opt(23, 59, 59, 1_500_000_000).unwrap();
+    let datetime = NaiveDate::from_ymd_opt(2016, 12, 31).unwrap().and_time(time);
+    assert_eq!(NaiveDateTime::decode(&datetime.encode().unwrap()).unwrap(), datetime);
+
+    let offset = FixedOffset::east_opt(-5 * 3600).unwrap();
+    let datetime = offset.from_utc_datetime(&datetime);
+    let encoded = datetime.encode().unwrap();
+    assert_eq!(encoded.len(), 16);
+    assert_eq!(DateTime::<FixedOffset>::decode(&encoded).unwrap(), datetime);
+    assert_eq!(DateTime::<Local>::decode(&encoded).unwrap(), datetime);
+
+    for duration in [Duration::milliseconds(-1_500), Duration::MIN, Duration::MAX] {
+        assert_eq!(Duration::decode(&duration.encode().unwrap()).unwrap(), duration);
+    }
+
+    let invalid = |err: Error| matches!(err.kind(), ErrorKind::InvalidData { .. });
+    assert!(invalid(NaiveDate::decode(&i32::MAX.encode().unwrap()).unwrap_err()));
+    assert!(invalid(NaiveTime::decode(&(86_400u32, 0u32).encode().unwrap()).unwrap_err()));
+    assert!(invalid(NaiveTime::decode(&(0u32, 1_000_000_000u32).encode().unwrap()).unwrap_err()));
+    assert!(invalid(Duration::decode(&(i64::MAX, 0u32).encode().unwrap()).unwrap_err()));
+
+    let err = DateTime::<FixedOffset>::decode(&[&encoded[..12], &86_400i32.encode().unwrap()].concat()).unwrap_err();
+    assert!(invalid(err));
+}
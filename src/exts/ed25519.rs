@@ -10,16 +10,18 @@
  * │                                          Imports                                           │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
-use crate::{Decode, Encode};
+use crate::borrow::owned;
+use crate::io::{Read, Write};
+use crate::schema::{Layout, Schema};
+use crate::{Decode, Encode, Error};
 use ed25519::{PublicKey, Signature};
-use std::io::{self, Read, Write};
 
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
  * │                               impl {En,De}code for PublicKey                               │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
 impl Encode for PublicKey {
-    type Error = io::Error;
+    type Error = Error;
 
     fn fast_size(&self) -> usize {
         self.to_bytes().fast_size()
@@ -36,7 +38,7 @@ impl Decode for PublicKey {
         if let Ok(key) = PublicKey::from_bytes(&bytes) {
             Ok((key, read))
         } else {
-            Err(io::Error::new(io::ErrorKind::InvalidData, "invalid public key"))
+            Err(Error::invalid_data("invalid public key"))
         }
     }
 
@@ -45,7 +47,7 @@ impl Decode for PublicKey {
         if let Ok(key) = PublicKey::from_bytes(&bytes) {
             Ok((key, read))
         } else {
-            Err(io::Error::new(io::ErrorKind::InvalidData, "invalid public key"))
+            Err(Error::invalid_data("invalid public key"))
         }
     }
 }
@@ -55,7 +57,7 @@ impl Decode for PublicKey {
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
 impl Encode for Signature {
-    type Error = io::Error;
+    type Error = Error;
 
     fn fast_size(&self) -> usize {
         self.to_bytes().fast_size()
@@ -73,6 +75,28 @@ impl Decode for Signature {
     }
 }
 
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                           impl Schema for {PublicKey,Signature}                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Schema for PublicKey {
+    fn schema() -> Layout {
+        <[u8; 32]>::schema()
+    }
+}
+
+impl Schema for Signature {
+    fn schema() -> Layout {
+        <[u8; 64]>::schema()
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                         owned!(..)                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+owned!(PublicKey, Signature);
+
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
  * │                                          #[test]                                           │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
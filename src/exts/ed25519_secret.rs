@@ -0,0 +1,130 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use crate::borrow::owned;
+use crate::io::{Read, Write};
+use crate::schema::{Field, Layout, Schema};
+use crate::secret;
+use crate::{Decode, Encode, Error, Trace};
+use alloc::vec::Vec;
+use ed25519::{Keypair, PublicKey, SecretKey, SECRET_KEY_LENGTH};
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                               impl {En,De}code for SecretKey                               │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Encode for SecretKey {
+    type Error = Error;
+
+    fn fast_size(&self) -> usize {
+        SECRET_KEY_LENGTH
+    }
+
+    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        Ok(writer.write_all(self.as_bytes())?)
+    }
+}
+
+impl Decode for SecretKey {
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+        let bytes = secret::read(reader)?;
+        match SecretKey::from_bytes(&bytes[..]) {
+            Ok(key) => Ok((key, SECRET_KEY_LENGTH)),
+            Err(_) => Err(Error::invalid_data("invalid secret key")),
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                impl {En,De}code for Keypair                                │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Encode for Keypair {
+    type Error = Error;
+
+    fn fast_size(&self) -> usize {
+        self.secret.fast_size() + self.public.fast_size()
+    }
+
+    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        self.secret.encode_into(&mut writer)?;
+        self.public.encode_into(writer)
+    }
+}
+
+impl Decode for Keypair {
+    fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
+        let (secret, read1) = SecretKey::decode_with_read_from(&mut reader)?;
+        let (public, read2) = PublicKey::decode_with_read_from(reader).map_err(|err| err.trace(None, read1))?;
+
+        // `Keypair::sign` trusts `public` to be derived from `secret`.
+        if PublicKey::from(&secret) != public {
+            return Err(Error::invalid_data("mismatched keypair").trace(None, read1));
+        }
+
+        Ok((Keypair { secret, public }, read1 + read2))
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                            impl Schema for {SecretKey,Keypair}                             │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Schema for SecretKey {
+    fn schema() -> Layout {
+        <[u8; SECRET_KEY_LENGTH]>::schema()
+    }
+}
+
+impl Schema for Keypair {
+    fn schema() -> Layout {
+        Layout::Struct {
+            name: "Keypair",
+            fields: Vec::from([
+                Field { name: "secret", layout: SecretKey::schema() },
+                Field { name: "public", layout: PublicKey::schema() },
+            ]),
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                         owned!(..)                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+owned!(SecretKey, Keypair);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(test)]
+#[test]
+fn keypair() {
+    use crate::ErrorKind;
+    use ed25519::{Signer, Verifier};
+    use rand::rngs::OsRng;
+
+    let keypair = Keypair::generate(&mut OsRng);
+    let encoded = keypair.encode().unwrap();
+    assert_eq!(encoded, keypair.to_bytes());
+
+    let decoded = Keypair::decode(&encoded).unwrap();
+    assert_eq!(decoded.secret.as_bytes(), keypair.secret.as_bytes());
+    assert!(keypair.public.verify(b"f0rm47", &decoded.sign(b"f0rm47")).is_ok());
+
+    let other = Keypair::generate(&mut OsRng);
+    let mut mismatched = encoded;
+    mismatched[32..].copy_from_slice(other.public.as_bytes());
+    let err = Keypair::decode(&mismatched).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidData { context: "mismatched keypair" }));
+}
@@ -10,9 +10,13 @@
  * │                                          Imports                                           │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
-use crate::{Decode, Encode};
-use std::collections::BinaryHeap;
-use std::io::{self, Read, Write};
+use crate::io::{Read, Write};
+use crate::limits;
+use crate::prefix::{self, Collection, DecodeCollection, U16};
+use crate::schema::{Layout, Schema};
+use crate::{Decode, Encode, Error, Segment, Trace};
+use alloc::boxed::Box;
+use alloc::collections::BinaryHeap;
 
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
  * │                             impl {En,De}code for BinaryHeap<T>                             │ *
@@ -21,16 +25,50 @@ use std::io::{self, Read, Write};
 impl<T> Encode for BinaryHeap<T>
 where
     T: Encode,
-    T::Error: From<io::Error>,
+    T::Error: From<Error> + Trace,
 {
     type Error = T::Error;
 
     fn size(&self) -> Result<usize, Self::Error> {
-        if self.len() > u16::MAX as usize {
-            return Err(io::Error::new(io::ErrorKind::InvalidInput, "heap.len() > u16::MAX").into());
-        }
+        prefix::size::<U16, _>(self)
+    }
+
+    fn fast_size(&self) -> usize {
+        prefix::fast_size::<U16, _>(self)
+    }
 
-        let mut size = (self.len() as u16).fast_size();
+    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+        prefix::encode_into::<U16, _, _>(self, writer)
+    }
+}
+
+impl<T> Decode for BinaryHeap<T>
+where
+    T: Decode + Ord,
+    T::Error: From<Error> + Trace,
+{
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+        prefix::decode_with_read_from::<U16, _, _>(reader)
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                         impl {Decode,}Collection for BinaryHeap<T>                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl<T> Collection for BinaryHeap<T>
+where
+    T: Encode,
+    T::Error: From<Error> + Trace,
+{
+    type Error = T::Error;
+
+    fn len(&self) -> usize {
+        BinaryHeap::len(self)
+    }
+
+    fn elems_size(&self) -> Result<usize, Self::Error> {
+        let mut size = 0;
         for elem in self {
             size += elem.size()?;
         }
@@ -38,39 +76,32 @@ where
         Ok(size)
     }
 
-    fn fast_size(&self) -> usize {
-        if self.len() > u16::MAX as usize {
-            0
-        } else {
-            (self.len() as u16).fast_size() + self.iter().next().map(|elem| elem.fast_size() * self.len()).unwrap_or(0)
-        }
+    fn elems_fast_size(&self) -> usize {
+        self.iter().next().map(|elem| elem.fast_size() * self.len()).unwrap_or(0)
     }
 
-    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
-        if self.len() > u16::MAX as usize {
-            Err(io::Error::new(io::ErrorKind::InvalidInput, "heap.len() > u16::MAX").into())
-        } else {
-            (self.len() as u16).encode_into(&mut writer)?;
-            for elem in self {
-                elem.encode_into(&mut writer)?;
-            }
-
-            Ok(())
+    fn encode_elems_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        for elem in self {
+            elem.encode_into(&mut writer)?;
         }
+
+        Ok(())
     }
 }
 
-impl<T> Decode for BinaryHeap<T>
+impl<T> DecodeCollection for BinaryHeap<T>
 where
     T: Decode + Ord,
-    T::Error: From<io::Error>,
+    T::Error: From<Error> + Trace,
 {
-    fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
-        let (len, mut read) = u16::decode_with_read_from(&mut reader)?;
+    fn decode_elems_with_read_from<R: Read>(len: usize, mut reader: R) -> Result<(Self, usize), Self::Error> {
+        limits::alloc::<T>(len)?;
+        let mut read = 0;
+        let mut heap = BinaryHeap::with_capacity(limits::capacity::<T>(len));
 
-        let mut heap = BinaryHeap::with_capacity(len as usize);
-        for _ in 0..len {
-            let (elem, readb) = T::decode_with_read_from(&mut reader)?;
+        for idx in 0..len {
+            let (elem, readb) = T::decode_with_read_from(&mut reader)
+                .map_err(|err| err.trace(Some(Segment::Index(idx)), read))?;
             heap.push(elem);
             read += readb;
         }
@@ -78,3 +109,13 @@ where
         Ok((heap, read))
     }
 }
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                               impl Schema for BinaryHeap<T>                                │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl<T: Schema> Schema for BinaryHeap<T> {
+    fn schema() -> Layout {
+        Layout::Seq { prefix: Box::new(U16::schema()), elem: Box::new(T::schema()) }
+    }
+}
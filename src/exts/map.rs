@@ -10,10 +10,26 @@
  * │                                          Imports                                           │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
-use crate::{Decode, Encode};
-use core::hash::Hash;
-use std::collections::{BTreeMap, HashMap};
-use std::io::{self, Read, Write};
+use crate::canonical::{self, Canonical, Strict};
+use crate::io::{Read, Write};
+use crate::limits;
+use crate::prefix::{self, Collection, DecodeCollection, U16};
+use crate::schema::{Layout, Schema};
+use crate::{Decode, Encode, Error, Segment, Trace};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use cfg_if::cfg_if;
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                       cfg_if! { .. }                                       │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+cfg_if! {
+    if #[cfg(feature = "std")] {
+        use core::hash::Hash;
+        use std::collections::HashMap;
+    }
+}
 
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
  * │                            impl {En,De}code for BTreeMap<K, V>                             │ *
@@ -23,16 +39,52 @@ impl<K, V, E> Encode for BTreeMap<K, V>
 where
     K: Encode<Error = E>,
     V: Encode<Error = E>,
-    E: From<io::Error>,
+    E: From<Error> + Trace,
 {
     type Error = E;
 
     fn size(&self) -> Result<usize, Self::Error> {
-        if self.len() > u16::MAX as usize {
-            return Err(io::Error::new(io::ErrorKind::InvalidInput, "map.len() > u16::MAX").into());
-        }
+        prefix::size::<U16, _>(self)
+    }
+
+    fn fast_size(&self) -> usize {
+        prefix::fast_size::<U16, _>(self)
+    }
+
+    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+        prefix::encode_into::<U16, _, _>(self, writer)
+    }
+}
+
+impl<K, V, E> Decode for BTreeMap<K, V>
+where
+    K: Encode<Error = E> + Decode + Ord,
+    V: Encode<Error = E> + Decode,
+    E: From<Error> + Trace,
+{
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+        prefix::decode_with_read_from::<U16, _, _>(reader)
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                        impl {Decode,}Collection for BTreeMap<K, V>                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl<K, V, E> Collection for BTreeMap<K, V>
+where
+    K: Encode<Error = E>,
+    V: Encode<Error = E>,
+    E: From<Error> + Trace,
+{
+    type Error = E;
+
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
 
-        let mut size = (self.len() as u16).size()?;
+    fn elems_size(&self) -> Result<usize, Self::Error> {
+        let mut size = 0;
         for (key, value) in self {
             size += key.size()?;
             size += value.size()?;
@@ -41,49 +93,38 @@ where
         Ok(size)
     }
 
-    fn fast_size(&self) -> usize {
-        if self.len() > u16::MAX as usize {
-            0
-        } else if self.is_empty() {
-            (0 as u16).fast_size()
-        } else {
-            let len = self.len();
-            let (key, value) = self.iter().next().unwrap();
-
-            (len as u16).fast_size() + (key.fast_size() + value.fast_size()) * len
-        }
+    fn elems_fast_size(&self) -> usize {
+        self.iter().next().map(|(key, value)| (key.fast_size() + value.fast_size()) * self.len()).unwrap_or(0)
     }
 
-    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
-        if self.len() > u16::MAX as usize {
-            Err(io::Error::new(io::ErrorKind::InvalidInput, "map.len() > u16::MAX").into())
-        } else {
-            (self.len() as u16).encode_into(&mut writer)?;
-            for (key, value) in self {
-                key.encode_into(&mut writer)?;
-                value.encode_into(&mut writer)?;
-            }
-
-            Ok(())
+    fn encode_elems_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        for (key, value) in self {
+            key.encode_into(&mut writer)?;
+            value.encode_into(&mut writer)?;
         }
+
+        Ok(())
     }
 }
 
-impl<K, V, E> Decode for BTreeMap<K, V>
+impl<K, V, E> DecodeCollection for BTreeMap<K, V>
 where
     K: Encode<Error = E> + Decode + Ord,
     V: Encode<Error = E> + Decode,
-    E: From<io::Error>,
+    E: From<Error> + Trace,
 {
-    fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
-        let (len, mut read) = u16::decode_with_read_from(&mut reader)?;
+    fn decode_elems_with_read_from<R: Read>(len: usize, mut reader: R) -> Result<(Self, usize), Self::Error> {
+        limits::alloc::<(K, V)>(len)?;
+        let mut read = 0;
 
         let mut map = BTreeMap::new();
-        for _ in 0..len {
-            let (key, readb) = K::decode_with_read_from(&mut reader)?;
+        for idx in 0..len {
+            let (key, readb) = K::decode_with_read_from(&mut reader)
+                .map_err(|err| err.trace(Some(Segment::Key(idx)), read))?;
             read += readb;
 
-            let (value, readb) = V::decode_with_read_from(&mut reader)?;
+            let (value, readb) = V::decode_with_read_from(&mut reader)
+                .map_err(|err| err.trace(Some(Segment::Value(idx)), read))?;
             read += readb;
 
             map.insert(key, value);
@@ -97,20 +138,59 @@ where
  * │                             impl {En,De}code for HashMap<K, V>                             │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
+#[cfg(feature = "std")]
 impl<K, V, E> Encode for HashMap<K, V>
 where
     K: Encode<Error = E>,
     V: Encode<Error = E>,
-    E: From<io::Error>,
+    E: From<Error> + Trace,
 {
     type Error = E;
 
     fn size(&self) -> Result<usize, Self::Error> {
-        if self.len() > u16::MAX as usize {
-            return Err(io::Error::new(io::ErrorKind::InvalidInput, "map.len() > u16::MAX").into());
-        }
+        prefix::size::<U16, _>(self)
+    }
+
+    fn fast_size(&self) -> usize {
+        prefix::fast_size::<U16, _>(self)
+    }
+
+    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+        prefix::encode_into::<U16, _, _>(self, writer)
+    }
+}
 
-        let mut size = (self.len() as u16).size()?;
+#[cfg(feature = "std")]
+impl<K, V, E> Decode for HashMap<K, V>
+where
+    K: Encode<Error = E> + Decode + Hash + Eq,
+    V: Encode<Error = E> + Decode,
+    E: From<Error> + Trace,
+{
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+        prefix::decode_with_read_from::<U16, _, _>(reader)
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                         impl {Decode,}Collection for HashMap<K, V>                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(feature = "std")]
+impl<K, V, E> Collection for HashMap<K, V>
+where
+    K: Encode<Error = E>,
+    V: Encode<Error = E>,
+    E: From<Error> + Trace,
+{
+    type Error = E;
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+
+    fn elems_size(&self) -> Result<usize, Self::Error> {
+        let mut size = 0;
         for (key, value) in self {
             size += key.size()?;
             size += value.size()?;
@@ -119,49 +199,39 @@ where
         Ok(size)
     }
 
-    fn fast_size(&self) -> usize {
-        if self.len() > u16::MAX as usize {
-            0
-        } else if self.is_empty() {
-            (0 as u16).fast_size()
-        } else {
-            let len = self.len();
-            let (key, value) = self.iter().next().unwrap();
-
-            (len as u16).fast_size() + (key.fast_size() + value.fast_size()) * len
-        }
+    fn elems_fast_size(&self) -> usize {
+        self.iter().next().map(|(key, value)| (key.fast_size() + value.fast_size()) * self.len()).unwrap_or(0)
     }
 
-    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
-        if self.len() > u16::MAX as usize {
-            Err(io::Error::new(io::ErrorKind::InvalidInput, "map.len() > u16::MAX").into())
-        } else {
-            (self.len() as u16).encode_into(&mut writer)?;
-            for (key, value) in self {
-                key.encode_into(&mut writer)?;
-                value.encode_into(&mut writer)?;
-            }
-
-            Ok(())
+    fn encode_elems_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        for (key, value) in self {
+            key.encode_into(&mut writer)?;
+            value.encode_into(&mut writer)?;
         }
+
+        Ok(())
     }
 }
 
-impl<K, V, E> Decode for HashMap<K, V>
+#[cfg(feature = "std")]
+impl<K, V, E> DecodeCollection for HashMap<K, V>
 where
     K: Encode<Error = E> + Decode + Hash + Eq,
     V: Encode<Error = E> + Decode,
-    E: From<io::Error>,
+    E: From<Error> + Trace,
 {
-    fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
-        let (len, mut read) = u16::decode_with_read_from(&mut reader)?;
+    fn decode_elems_with_read_from<R: Read>(len: usize, mut reader: R) -> Result<(Self, usize), Self::Error> {
+        limits::alloc::<(K, V)>(len)?;
+        let mut read = 0;
 
-        let mut map = HashMap::with_capacity(len as usize);
-        for _ in 0..len {
-            let (key, readb) = K::decode_with_read_from(&mut reader)?;
+        let mut map = HashMap::with_capacity(limits::capacity::<(K, V)>(len));
+        for idx in 0..len {
+            let (key, readb) = K::decode_with_read_from(&mut reader)
+                .map_err(|err| err.trace(Some(Segment::Key(idx)), read))?;
             read += readb;
 
-            let (value, readb) = V::decode_with_read_from(&mut reader)?;
+            let (value, readb) = V::decode_with_read_from(&mut reader)
+                .map_err(|err| err.trace(Some(Segment::Value(idx)), read))?;
             read += readb;
 
             map.insert(key, value);
@@ -170,3 +240,155 @@ where
         Ok((map, read))
     }
 }
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                           Macros                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+macro_rules! canonical {
+    ($map:ident, $($bound:tt)+) => {
+        canonical!(@ENCODE Canonical, $map);
+        canonical!(@ENCODE Strict, $map);
+
+        impl<K, V, E> Decode for Canonical<$map<K, V>>
+        where
+            K: Encode<Error = E> + Decode + $($bound)+,
+            V: Encode<Error = E> + Decode,
+            E: From<Error> + Trace,
+        {
+            fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+                prefix::decode_with_read_from::<U16, _, _>(reader)
+            }
+        }
+
+        impl<K, V, E> Decode for Strict<$map<K, V>>
+        where
+            K: Encode<Error = E> + Decode + $($bound)+,
+            V: Encode<Error = E> + Decode,
+            E: From<Error> + Trace,
+        {
+            fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+                prefix::decode_with_read_from::<U16, _, _>(reader)
+            }
+        }
+
+        impl<K, V, E> DecodeCollection for Canonical<$map<K, V>>
+        where
+            K: Encode<Error = E> + Decode + $($bound)+,
+            V: Encode<Error = E> + Decode,
+            E: From<Error> + Trace,
+        {
+            fn decode_elems_with_read_from<R: Read>(len: usize, reader: R) -> Result<(Self, usize), Self::Error> {
+                let (map, read) = <$map<K, V>>::decode_elems_with_read_from(len, reader)?;
+                Ok((Canonical(map), read))
+            }
+        }
+
+        impl<K, V, E> DecodeCollection for Strict<$map<K, V>>
+        where
+            K: Encode<Error = E> + Decode + $($bound)+,
+            V: Encode<Error = E> + Decode,
+            E: From<Error> + Trace,
+        {
+            fn decode_elems_with_read_from<R: Read>(len: usize, mut reader: R) -> Result<(Self, usize), Self::Error> {
+                limits::alloc::<(K, V)>(len)?;
+                let mut read = 0;
+                let mut prev = None;
+
+                let mut map = $map::new();
+                for idx in 0..len {
+                    let (key, readb) = K::decode_with_read_from(&mut reader)
+                        .and_then(|(key, readb)| canonical::check_sorted(&mut prev, &key).map(|()| (key, readb)))
+                        .map_err(|err| err.trace(Some(Segment::Key(idx)), read))?;
+                    read += readb;
+
+                    let (value, readb) = V::decode_with_read_from(&mut reader)
+                        .map_err(|err| err.trace(Some(Segment::Value(idx)), read))?;
+                    read += readb;
+
+                    map.insert(key, value);
+                }
+
+                Ok((Strict(map), read))
+            }
+        }
+    };
+
+    (@ENCODE $wrapper:ident, $map:ident) => {
+        impl<K, V, E> Encode for $wrapper<$map<K, V>>
+        where
+            K: Encode<Error = E>,
+            V: Encode<Error = E>,
+            E: From<Error> + Trace,
+        {
+            type Error = E;
+
+            fn size(&self) -> Result<usize, Self::Error> {
+                prefix::size::<U16, _>(self)
+            }
+
+            fn fast_size(&self) -> usize {
+                prefix::fast_size::<U16, _>(self)
+            }
+
+            fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+                prefix::encode_into::<U16, _, _>(self, writer)
+            }
+        }
+
+        impl<K, V, E> Collection for $wrapper<$map<K, V>>
+        where
+            K: Encode<Error = E>,
+            V: Encode<Error = E>,
+            E: From<Error> + Trace,
+        {
+            type Error = E;
+
+            fn len(&self) -> usize {
+                self.0.len()
+            }
+
+            fn elems_size(&self) -> Result<usize, Self::Error> {
+                self.0.elems_size()
+            }
+
+            fn elems_fast_size(&self) -> usize {
+                self.0.elems_fast_size()
+            }
+
+            fn encode_elems_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+                for (key, value) in canonical::sorted(self.0.iter())? {
+                    writer.write_all(&key).map_err(Error::from)?;
+                    value.encode_into(&mut writer)?;
+                }
+
+                Ok(())
+            }
+        }
+    };
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                       canonical!(..)                                       │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+canonical!(BTreeMap, Ord);
+#[cfg(feature = "std")]
+canonical!(HashMap, Hash + Eq);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                           impl Schema for {BTree,Hash}Map<K, V>                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl<K: Schema, V: Schema> Schema for BTreeMap<K, V> {
+    fn schema() -> Layout {
+        Layout::Map { prefix: Box::new(U16::schema()), key: Box::new(K::schema()), value: Box::new(V::schema()) }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: Schema, V: Schema> Schema for HashMap<K, V> {
+    fn schema() -> Layout {
+        BTreeMap::<K, V>::schema()
+    }
+}
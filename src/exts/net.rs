@@ -10,92 +10,74 @@
  * │                                          Imports                                           │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
-use crate::{Decode, Encode};
-use std::io::{self, Read, Write};
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use crate::borrow::owned;
+use crate::endian::BigEndian;
+use crate::io::{Read, Write};
+use crate::schema::{Field, Layout, Schema, Variant};
+use crate::tagged;
+use crate::{Decode, Encode, Error};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
 
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
  * │                            impl {En,De}code for {Ip,Socket}Addr                            │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
 impl Encode for IpAddr {
-    type Error = io::Error;
+    type Error = Error;
 
     fn fast_size(&self) -> usize {
         match self {
-            IpAddr::V4(addr) => 4u8.fast_size() + addr.fast_size(),
-            IpAddr::V6(addr) => 6u8.fast_size() + addr.fast_size(),
+            IpAddr::V4(addr) => tagged::variant_fast_size(4u8, addr),
+            IpAddr::V6(addr) => tagged::variant_fast_size(6u8, addr),
         }
     }
 
-    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
         match self {
-            IpAddr::V4(addr) => {
-                4u8.encode_into(&mut writer)?;
-                addr.encode_into(writer)
-            }
-            IpAddr::V6(addr) => {
-                6u8.encode_into(&mut writer)?;
-                addr.encode_into(writer)
-            }
+            IpAddr::V4(addr) => tagged::encode_variant(4u8, addr, writer),
+            IpAddr::V6(addr) => tagged::encode_variant(6u8, addr, writer),
         }
     }
 }
 
 impl Encode for SocketAddr {
-    type Error = io::Error;
+    type Error = Error;
 
     fn fast_size(&self) -> usize {
         match self {
-            SocketAddr::V4(addr) => 4u8.fast_size() + addr.fast_size(),
-            SocketAddr::V6(addr) => 4u8.fast_size() + addr.fast_size(),
+            SocketAddr::V4(addr) => tagged::variant_fast_size(4u8, addr),
+            SocketAddr::V6(addr) => tagged::variant_fast_size(6u8, addr),
         }
     }
 
-    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
         match self {
-            SocketAddr::V4(addr) => {
-                4u8.encode_into(&mut writer)?;
-                addr.encode_into(writer)
-            }
-            SocketAddr::V6(addr) => {
-                6u8.encode_into(&mut writer)?;
-                addr.encode_into(writer)
-            }
+            SocketAddr::V4(addr) => tagged::encode_variant(4u8, addr, writer),
+            SocketAddr::V6(addr) => tagged::encode_variant(6u8, addr, writer),
         }
     }
 }
 
 impl Decode for IpAddr {
     fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
-        let (tag, read1) = u8::decode_with_read_from(&mut reader)?;
+        let (tag, read) = u8::decode_with_read_from(&mut reader)?;
         match tag {
-            4 => {
-                let (addr, read2) = Ipv4Addr::decode_with_read_from(&mut reader)?;
-                Ok((IpAddr::V4(addr), read1 + read2))
-            },
-            6 => {
-                let (addr, read2) = Ipv6Addr::decode_with_read_from(&mut reader)?;
-                Ok((IpAddr::V6(addr), read1 + read2))
-            },
-            _ => Err(io::Error::new(io::ErrorKind::InvalidData, "expected '4' or '6'")),
+            4 => tagged::decode_variant(read, reader, IpAddr::V4),
+            6 => tagged::decode_variant(read, reader, IpAddr::V6),
+            _ => Err(Error::invalid_tag("IpAddr", tag as u64)),
         }
     }
 }
 
 impl Decode for SocketAddr {
     fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
-        let (tag, read1) = u8::decode_with_read_from(&mut reader)?;
+        let (tag, read) = u8::decode_with_read_from(&mut reader)?;
         match tag {
-            4 => {
-                let (addr, read2) = SocketAddrV4::decode_with_read_from(&mut reader)?;
-                Ok((SocketAddr::V4(addr), read1 + read2))
-            },
-            6 => {
-                let (addr, read2) = SocketAddrV6::decode_with_read_from(&mut reader)?;
-                Ok((SocketAddr::V6(addr), read1 + read2))
-            },
-            _ => Err(io::Error::new(io::ErrorKind::InvalidData, "expected '4' or '6'")),
+            4 => tagged::decode_variant(read, reader, SocketAddr::V4),
+            6 => tagged::decode_variant(read, reader, SocketAddr::V6),
+            _ => Err(Error::invalid_tag("SocketAddr", tag as u64)),
         }
     }
 }
@@ -105,7 +87,7 @@ impl Decode for SocketAddr {
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
 impl Encode for Ipv4Addr {
-    type Error = io::Error;
+    type Error = Error;
 
     fn fast_size(&self) -> usize {
         self.octets().fast_size()
@@ -117,7 +99,7 @@ impl Encode for Ipv4Addr {
 }
 
 impl Encode for Ipv6Addr {
-    type Error = io::Error;
+    type Error = Error;
 
     fn fast_size(&self) -> usize {
         self.octets().fast_size()
@@ -147,7 +129,7 @@ impl Decode for Ipv6Addr {
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
 impl Encode for SocketAddrV4 {
-    type Error = io::Error;
+    type Error = Error;
 
     fn fast_size(&self) -> usize {
         self.ip().fast_size() + self.port().fast_size()
@@ -162,7 +144,7 @@ impl Encode for SocketAddrV4 {
 }
 
 impl Encode for SocketAddrV6 {
-    type Error = io::Error;
+    type Error = Error;
 
     fn fast_size(&self) -> usize {
         self.ip().fast_size()
@@ -200,3 +182,209 @@ impl Decode for SocketAddrV6 {
         Ok((Self::new(ip, port, flowinfo, scope_id), read1 + read2 + read3 + read4))
     }
 }
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                     impl {En,De}code for BigEndian<SocketAddr{,V4,V6}>                     │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Encode for BigEndian<SocketAddr> {
+    type Error = Error;
+
+    fn fast_size(&self) -> usize {
+        match self.0 {
+            SocketAddr::V4(addr) => tagged::variant_fast_size(4u8, &BigEndian(addr)),
+            SocketAddr::V6(addr) => tagged::variant_fast_size(6u8, &BigEndian(addr)),
+        }
+    }
+
+    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+        match self.0 {
+            SocketAddr::V4(addr) => tagged::encode_variant(4u8, &BigEndian(addr), writer),
+            SocketAddr::V6(addr) => tagged::encode_variant(6u8, &BigEndian(addr), writer),
+        }
+    }
+}
+
+impl Encode for BigEndian<SocketAddrV4> {
+    type Error = Error;
+
+    fn fast_size(&self) -> usize {
+        self.0.ip().fast_size() + BigEndian(self.0.port()).fast_size()
+    }
+
+    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        self.0.ip().encode_into(&mut writer)?;
+        BigEndian(self.0.port()).encode_into(&mut writer)?;
+
+        Ok(())
+    }
+}
+
+impl Encode for BigEndian<SocketAddrV6> {
+    type Error = Error;
+
+    fn fast_size(&self) -> usize {
+        self.0.ip().fast_size()
+            + BigEndian(self.0.port()).fast_size()
+            + BigEndian(self.0.flowinfo()).fast_size()
+            + BigEndian(self.0.scope_id()).fast_size()
+    }
+
+    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        self.0.ip().encode_into(&mut writer)?;
+        BigEndian(self.0.port()).encode_into(&mut writer)?;
+        BigEndian(self.0.flowinfo()).encode_into(&mut writer)?;
+        BigEndian(self.0.scope_id()).encode_into(&mut writer)?;
+
+        Ok(())
+    }
+}
+
+impl Decode for BigEndian<SocketAddr> {
+    fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
+        let (tag, read) = u8::decode_with_read_from(&mut reader)?;
+        match tag {
+            4 => tagged::decode_variant(read, reader, |BigEndian(addr)| BigEndian(SocketAddr::V4(addr))),
+            6 => tagged::decode_variant(read, reader, |BigEndian(addr)| BigEndian(SocketAddr::V6(addr))),
+            _ => Err(Error::invalid_tag("SocketAddr", tag as u64)),
+        }
+    }
+}
+
+impl Decode for BigEndian<SocketAddrV4> {
+    fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
+        let (ip, read1) = Ipv4Addr::decode_with_read_from(&mut reader)?;
+        let (BigEndian(port), read2) = BigEndian::<u16>::decode_with_read_from(&mut reader)?;
+
+        Ok((BigEndian(SocketAddrV4::new(ip, port)), read1 + read2))
+    }
+}
+
+impl Decode for BigEndian<SocketAddrV6> {
+    fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
+        let (ip, read1) = Ipv6Addr::decode_with_read_from(&mut reader)?;
+        let (BigEndian(port), read2) = BigEndian::<u16>::decode_with_read_from(&mut reader)?;
+        let (BigEndian(flowinfo), read3) = BigEndian::<u32>::decode_with_read_from(&mut reader)?;
+        let (BigEndian(scope_id), read4) = BigEndian::<u32>::decode_with_read_from(&mut reader)?;
+
+        let addr = SocketAddrV6::new(ip, port, flowinfo, scope_id);
+        Ok((BigEndian(addr), read1 + read2 + read3 + read4))
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                          impl Schema for {Ip,Socket}Addr{,V4,V6}                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Schema for IpAddr {
+    fn schema() -> Layout {
+        addr("IpAddr", Ipv4Addr::schema(), Ipv6Addr::schema())
+    }
+}
+
+impl Schema for Ipv4Addr {
+    fn schema() -> Layout {
+        <[u8; 4]>::schema()
+    }
+}
+
+impl Schema for Ipv6Addr {
+    fn schema() -> Layout {
+        <[u8; 16]>::schema()
+    }
+}
+
+impl Schema for SocketAddr {
+    fn schema() -> Layout {
+        addr("SocketAddr", SocketAddrV4::schema(), SocketAddrV6::schema())
+    }
+}
+
+impl Schema for SocketAddrV4 {
+    fn schema() -> Layout {
+        socket_v4::<u16>()
+    }
+}
+
+impl Schema for SocketAddrV6 {
+    fn schema() -> Layout {
+        socket_v6::<u16, u32>()
+    }
+}
+
+impl Schema for BigEndian<SocketAddr> {
+    fn schema() -> Layout {
+        addr("SocketAddr", BigEndian::<SocketAddrV4>::schema(), BigEndian::<SocketAddrV6>::schema())
+    }
+}
+
+impl Schema for BigEndian<SocketAddrV4> {
+    fn schema() -> Layout {
+        socket_v4::<BigEndian<u16>>()
+    }
+}
+
+impl Schema for BigEndian<SocketAddrV6> {
+    fn schema() -> Layout {
+        socket_v6::<BigEndian<u16>, BigEndian<u32>>()
+    }
+}
+
+fn addr(name: &'static str, v4: Layout, v6: Layout) -> Layout {
+    let variant = |name, tag, layout| Variant { name, tag, fields: Vec::from([Field { name: "0", layout }]) };
+    Layout::Enum {
+        name,
+        tag: Box::new(u8::schema()),
+        prefix: None,
+        variants: Vec::from([variant("V4", 4, v4), variant("V6", 6, v6)]),
+    }
+}
+
+fn socket_v4<Port: Schema>() -> Layout {
+    Layout::Struct {
+        name: "SocketAddrV4",
+        fields: Vec::from([
+            Field { name: "ip", layout: Ipv4Addr::schema() },
+            Field { name: "port", layout: Port::schema() },
+        ]),
+    }
+}
+
+fn socket_v6<Port: Schema, Int: Schema>() -> Layout {
+    Layout::Struct {
+        name: "SocketAddrV6",
+        fields: Vec::from([
+            Field { name: "ip", layout: Ipv6Addr::schema() },
+            Field { name: "port", layout: Port::schema() },
+            Field { name: "flowinfo", layout: Int::schema() },
+            Field { name: "scope_id", layout: Int::schema() },
+        ]),
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                         owned!(..)                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+owned!(IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6);
+owned!(BigEndian<SocketAddr>, BigEndian<SocketAddrV4>, BigEndian<SocketAddrV6>);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(test)]
+#[test]
+fn big_endian() {
+    let addr = SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 8080);
+    assert_eq!(addr.encode().unwrap(), [127, 0, 0, 1, 0x90, 0x1f]);
+
+    let encoded = BigEndian(addr).encode().unwrap();
+    assert_eq!(encoded, [127, 0, 0, 1, 0x1f, 0x90]);
+    assert_eq!(BigEndian::<SocketAddrV4>::decode(&encoded).unwrap(), BigEndian(addr));
+
+    let addr = SocketAddr::V6(SocketAddrV6::new(Ipv6Addr::LOCALHOST, 8080, 1, 2));
+    let encoded = BigEndian(addr).encode().unwrap();
+    assert_eq!(encoded.len(), BigEndian(addr).fast_size());
+    assert_eq!(BigEndian::<SocketAddr>::decode(&encoded).unwrap(), BigEndian(addr));
+}
@@ -10,17 +10,20 @@
  * │                                          Imports                                           │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
-use crate::{Decode, Encode};
+use crate::borrow::owned;
+use crate::io::{Read, Write};
+use crate::schema::{Field, Layout, Schema};
+use crate::{Decode, Encode, Error};
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
 use pow::Proofs;
-use std::collections::BTreeMap;
-use std::io::{self, Read, Write};
 
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
  * │                                impl {En,De}code for Proofs                                 │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
 impl Encode for Proofs {
-    type Error = io::Error;
+    type Error = Error;
 
     fn fast_size(&self) -> usize {
         self.description().fast_size()
@@ -31,7 +34,7 @@ impl Encode for Proofs {
 
     fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
         if self.levels() > u16::MAX as usize || self.proofs() > u16::MAX as usize {
-            Err(io::Error::new(io::ErrorKind::InvalidInput, "levels and proofs should be < u16::MAX"))
+            Err(Error::length_overflow(u16::MAX as u64, self.levels().max(self.proofs()) as u64))
         } else {
             self.description().encode_into(&mut writer)?;
             (self.levels() as u16).encode_into(&mut writer)?;
@@ -52,6 +55,30 @@ impl Decode for Proofs {
     }
 }
 
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                   impl Schema for Proofs                                   │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Schema for Proofs {
+    fn schema() -> Layout {
+        Layout::Struct {
+            name: "Proofs",
+            fields: Vec::from([
+                Field { name: "description", layout: <[u8]>::schema() },
+                Field { name: "levels", layout: u16::schema() },
+                Field { name: "proofs", layout: u16::schema() },
+                Field { name: "nodes", layout: BTreeMap::<usize, [u8; 32]>::schema() },
+            ]),
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                         owned!(..)                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+owned!(Proofs);
+
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
  * │                                          #[test]                                           │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
@@ -10,10 +10,26 @@
  * │                                          Imports                                           │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
-use crate::{Decode, Encode};
-use core::hash::Hash;
-use std::collections::{BTreeSet, HashSet};
-use std::io::{self, Read, Write};
+use crate::canonical::{self, Canonical, Strict};
+use crate::io::{Read, Write};
+use crate::limits;
+use crate::prefix::{self, Collection, DecodeCollection, U16};
+use crate::schema::{Layout, Schema};
+use crate::{Decode, Encode, Error, Segment, Trace};
+use alloc::boxed::Box;
+use alloc::collections::BTreeSet;
+use cfg_if::cfg_if;
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                       cfg_if! { .. }                                       │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+cfg_if! {
+    if #[cfg(feature = "std")] {
+        use core::hash::Hash;
+        use std::collections::HashSet;
+    }
+}
 
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
  * │                              impl {En,De}code for BTreeSet<T>                              │ *
@@ -22,16 +38,50 @@ use std::io::{self, Read, Write};
 impl<T> Encode for BTreeSet<T>
 where
     T: Encode,
-    T::Error: From<io::Error>,
+    T::Error: From<Error> + Trace,
 {
     type Error = T::Error;
 
     fn size(&self) -> Result<usize, Self::Error> {
-        if self.len() > u16::MAX as usize {
-            return Err(io::Error::new(io::ErrorKind::InvalidInput, "set.len() > u16::MAX").into());
-        }
+        prefix::size::<U16, _>(self)
+    }
+
+    fn fast_size(&self) -> usize {
+        prefix::fast_size::<U16, _>(self)
+    }
+
+    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+        prefix::encode_into::<U16, _, _>(self, writer)
+    }
+}
+
+impl<T> Decode for BTreeSet<T>
+where
+    T: Decode + Ord,
+    T::Error: From<Error> + Trace,
+{
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+        prefix::decode_with_read_from::<U16, _, _>(reader)
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                          impl {Decode,}Collection for BTreeSet<T>                          │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl<T> Collection for BTreeSet<T>
+where
+    T: Encode,
+    T::Error: From<Error> + Trace,
+{
+    type Error = T::Error;
+
+    fn len(&self) -> usize {
+        BTreeSet::len(self)
+    }
 
-        let mut size = (self.len() as u16).size()?;
+    fn elems_size(&self) -> Result<usize, Self::Error> {
+        let mut size = 0;
         for val in self {
             size += val.size()?;
         }
@@ -39,44 +89,32 @@ where
         Ok(size)
     }
 
-    fn fast_size(&self) -> usize {
-        if self.len() > u16::MAX as usize {
-            0
-        } else if self.is_empty() {
-            (0 as u16).fast_size()
-        } else {
-            let len = self.len();
-            let val = self.iter().next().unwrap();
-
-            (len as u16).fast_size() + val.fast_size() * len
-        }
+    fn elems_fast_size(&self) -> usize {
+        self.iter().next().map(|val| val.fast_size() * self.len()).unwrap_or(0)
     }
 
-    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
-        if self.len() > u16::MAX as usize {
-            Err(io::Error::new(io::ErrorKind::InvalidInput, "set.len() > u16::MAX").into())
-        } else {
-            (self.len() as u16).encode_into(&mut writer)?;
-            for val in self {
-                val.encode_into(&mut writer)?;
-            }
-
-            Ok(())
+    fn encode_elems_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        for val in self {
+            val.encode_into(&mut writer)?;
         }
+
+        Ok(())
     }
 }
 
-impl<T> Decode for BTreeSet<T>
+impl<T> DecodeCollection for BTreeSet<T>
 where
     T: Decode + Ord,
-    T::Error: From<io::Error>,
+    T::Error: From<Error> + Trace,
 {
-    fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
-        let (len, mut read) = u16::decode_with_read_from(&mut reader)?;
+    fn decode_elems_with_read_from<R: Read>(len: usize, mut reader: R) -> Result<(Self, usize), Self::Error> {
+        limits::alloc::<T>(len)?;
+        let mut read = 0;
 
         let mut set = BTreeSet::new();
-        for _ in 0..len {
-            let (val, readb) = T::decode_with_read_from(&mut reader)?;
+        for idx in 0..len {
+            let (val, readb) = T::decode_with_read_from(&mut reader)
+                .map_err(|err| err.trace(Some(Segment::Index(idx)), read))?;
             read += readb;
 
             set.insert(val);
@@ -90,19 +128,56 @@ where
  * │                              impl {En,De}code for HashSet<T>                               │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
+#[cfg(feature = "std")]
 impl<T> Encode for HashSet<T>
 where
     T: Encode,
-    T::Error: From<io::Error>,
+    T::Error: From<Error> + Trace,
 {
     type Error = T::Error;
 
     fn size(&self) -> Result<usize, Self::Error> {
-        if self.len() > u16::MAX as usize {
-            return Err(io::Error::new(io::ErrorKind::InvalidInput, "set.len() > u16::MAX").into());
-        }
+        prefix::size::<U16, _>(self)
+    }
+
+    fn fast_size(&self) -> usize {
+        prefix::fast_size::<U16, _>(self)
+    }
+
+    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+        prefix::encode_into::<U16, _, _>(self, writer)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> Decode for HashSet<T>
+where
+    T: Decode + Hash + Eq,
+    T::Error: From<Error> + Trace,
+{
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+        prefix::decode_with_read_from::<U16, _, _>(reader)
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                          impl {Decode,}Collection for HashSet<T>                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(feature = "std")]
+impl<T> Collection for HashSet<T>
+where
+    T: Encode,
+    T::Error: From<Error> + Trace,
+{
+    type Error = T::Error;
 
-        let mut size = (self.len() as u16).size()?;
+    fn len(&self) -> usize {
+        HashSet::len(self)
+    }
+
+    fn elems_size(&self) -> Result<usize, Self::Error> {
+        let mut size = 0;
         for val in self {
             size += val.size()?;
         }
@@ -110,44 +185,33 @@ where
         Ok(size)
     }
 
-    fn fast_size(&self) -> usize {
-        if self.len() > u16::MAX as usize {
-            0
-        } else if self.is_empty() {
-            (0 as u16).fast_size()
-        } else {
-            let len = self.len();
-            let val = self.iter().next().unwrap();
-
-            (len as u16).fast_size() + val.fast_size() * len
-        }
+    fn elems_fast_size(&self) -> usize {
+        self.iter().next().map(|val| val.fast_size() * self.len()).unwrap_or(0)
     }
 
-    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
-        if self.len() > u16::MAX as usize {
-            Err(io::Error::new(io::ErrorKind::InvalidInput, "set.len() > u16::MAX").into())
-        } else {
-            (self.len() as u16).encode_into(&mut writer)?;
-            for val in self {
-                val.encode_into(&mut writer)?;
-            }
-
-            Ok(())
+    fn encode_elems_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        for val in self {
+            val.encode_into(&mut writer)?;
         }
+
+        Ok(())
     }
 }
 
-impl<T> Decode for HashSet<T>
+#[cfg(feature = "std")]
+impl<T> DecodeCollection for HashSet<T>
 where
     T: Decode + Hash + Eq,
-    T::Error: From<io::Error>,
+    T::Error: From<Error> + Trace,
 {
-    fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
-        let (len, mut read) = u16::decode_with_read_from(&mut reader)?;
+    fn decode_elems_with_read_from<R: Read>(len: usize, mut reader: R) -> Result<(Self, usize), Self::Error> {
+        limits::alloc::<T>(len)?;
+        let mut read = 0;
 
-        let mut set = HashSet::with_capacity(len as usize);
-        for _ in 0..len {
-            let (val, readb) = T::decode_with_read_from(&mut reader)?;
+        let mut set = HashSet::with_capacity(limits::capacity::<T>(len));
+        for idx in 0..len {
+            let (val, readb) = T::decode_with_read_from(&mut reader)
+                .map_err(|err| err.trace(Some(Segment::Index(idx)), read))?;
             read += readb;
 
             set.insert(val);
@@ -156,3 +220,144 @@ where
         Ok((set, read))
     }
 }
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                           Macros                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+macro_rules! canonical {
+    ($set:ident, $($bound:tt)+) => {
+        canonical!(@ENCODE Canonical, $set);
+        canonical!(@ENCODE Strict, $set);
+
+        impl<T> Decode for Canonical<$set<T>>
+        where
+            T: Decode + $($bound)+,
+            T::Error: From<Error> + Trace,
+        {
+            fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+                prefix::decode_with_read_from::<U16, _, _>(reader)
+            }
+        }
+
+        impl<T> Decode for Strict<$set<T>>
+        where
+            T: Decode + $($bound)+,
+            T::Error: From<Error> + Trace,
+        {
+            fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+                prefix::decode_with_read_from::<U16, _, _>(reader)
+            }
+        }
+
+        impl<T> DecodeCollection for Canonical<$set<T>>
+        where
+            T: Decode + $($bound)+,
+            T::Error: From<Error> + Trace,
+        {
+            fn decode_elems_with_read_from<R: Read>(len: usize, reader: R) -> Result<(Self, usize), Self::Error> {
+                let (set, read) = <$set<T>>::decode_elems_with_read_from(len, reader)?;
+                Ok((Canonical(set), read))
+            }
+        }
+
+        impl<T> DecodeCollection for Strict<$set<T>>
+        where
+            T: Decode + $($bound)+,
+            T::Error: From<Error> + Trace,
+        {
+            fn decode_elems_with_read_from<R: Read>(len: usize, mut reader: R) -> Result<(Self, usize), Self::Error> {
+                limits::alloc::<T>(len)?;
+                let mut read = 0;
+                let mut prev = None;
+
+                let mut set = $set::new();
+                for idx in 0..len {
+                    let (val, readb) = T::decode_with_read_from(&mut reader)
+                        .and_then(|(val, readb)| canonical::check_sorted(&mut prev, &val).map(|()| (val, readb)))
+                        .map_err(|err| err.trace(Some(Segment::Index(idx)), read))?;
+                    read += readb;
+
+                    set.insert(val);
+                }
+
+                Ok((Strict(set), read))
+            }
+        }
+    };
+
+    (@ENCODE $wrapper:ident, $set:ident) => {
+        impl<T> Encode for $wrapper<$set<T>>
+        where
+            T: Encode,
+            T::Error: From<Error> + Trace,
+        {
+            type Error = T::Error;
+
+            fn size(&self) -> Result<usize, Self::Error> {
+                prefix::size::<U16, _>(self)
+            }
+
+            fn fast_size(&self) -> usize {
+                prefix::fast_size::<U16, _>(self)
+            }
+
+            fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+                prefix::encode_into::<U16, _, _>(self, writer)
+            }
+        }
+
+        impl<T> Collection for $wrapper<$set<T>>
+        where
+            T: Encode,
+            T::Error: From<Error> + Trace,
+        {
+            type Error = T::Error;
+
+            fn len(&self) -> usize {
+                self.0.len()
+            }
+
+            fn elems_size(&self) -> Result<usize, Self::Error> {
+                self.0.elems_size()
+            }
+
+            fn elems_fast_size(&self) -> usize {
+                self.0.elems_fast_size()
+            }
+
+            fn encode_elems_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+                for (val, ()) in canonical::sorted(self.0.iter().map(|val| (val, ())))? {
+                    writer.write_all(&val).map_err(Error::from)?;
+                }
+
+                Ok(())
+            }
+        }
+    };
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                       canonical!(..)                                       │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+canonical!(BTreeSet, Ord);
+#[cfg(feature = "std")]
+canonical!(HashSet, Hash + Eq);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                             impl Schema for {BTree,Hash}Set<T>                             │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl<T: Schema> Schema for BTreeSet<T> {
+    fn schema() -> Layout {
+        Layout::Seq { prefix: Box::new(U16::schema()), elem: Box::new(T::schema()) }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Schema> Schema for HashSet<T> {
+    fn schema() -> Layout {
+        BTreeSet::<T>::schema()
+    }
+}
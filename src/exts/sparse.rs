@@ -10,16 +10,19 @@
  * │                                          Imports                                           │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
-use crate::{Decode, DecodeRef, Encode};
+use crate::borrow::owned;
+use crate::io::{Read, Write};
+use crate::schema::{Layout, Schema};
+use crate::{Decode, DecodeRef, Encode, Error};
+use alloc::vec::Vec;
 use sparse::Proof;
-use std::io::{self, Read, Write};
 
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
  * │                                 impl {En,De}code for Proof                                 │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
 impl Encode for Proof {
-    type Error = io::Error;
+    type Error = Error;
 
     fn fast_size(&self) -> usize {
         Proof::size(self)
@@ -36,7 +39,7 @@ impl Decode for Proof {
         if let Ok(proof) = Proof::from_bytes(buf) {
             Ok((proof, read))
         } else {
-            Err(io::Error::new(io::ErrorKind::InvalidData, "invalid proof data"))
+            Err(Error::invalid_data("invalid proof data"))
         }
     }
 
@@ -45,11 +48,27 @@ impl Decode for Proof {
         if let Ok(proof) = Proof::from_bytes(&buf) {
             Ok((proof, read))
         } else {
-            Err(io::Error::new(io::ErrorKind::InvalidData, "invalid proof data"))
+            Err(Error::invalid_data("invalid proof data"))
         }
     }
 }
 
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                   impl Schema for Proof                                    │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Schema for Proof {
+    fn schema() -> Layout {
+        <[u8]>::schema()
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                         owned!(..)                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+owned!(Proof);
+
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
  * │                                          #[test]                                           │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
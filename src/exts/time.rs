@@ -0,0 +1,175 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use crate::borrow::owned;
+use crate::io::{Read, Write};
+use crate::schema::{Field, Layout, Schema};
+use crate::{Decode, Encode, Error};
+use alloc::vec::Vec;
+use cfg_if::cfg_if;
+use core::time::Duration;
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                       cfg_if! { .. }                                       │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+cfg_if! {
+    if #[cfg(feature = "std")] {
+        use core::convert::TryFrom;
+        use std::time::{SystemTime, UNIX_EPOCH};
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                               impl {En,De}code for Duration                                │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Encode for Duration {
+    type Error = Error;
+
+    fn fast_size(&self) -> usize {
+        self.as_secs().fast_size() + self.subsec_nanos().fast_size()
+    }
+
+    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        self.as_secs().encode_into(&mut writer)?;
+        self.subsec_nanos().encode_into(writer)
+    }
+}
+
+impl Decode for Duration {
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+        let ((secs, nanos), len) = <(u64, u32)>::decode_with_read_from(reader)?;
+        if nanos >= 1_000_000_000 {
+            return Err(Error::invalid_data("invalid duration"));
+        }
+
+        Ok((Duration::new(secs, nanos), len))
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                              impl {En,De}code for SystemTime                               │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Encoded as the number of seconds since `UNIX_EPOCH` (negative before it) followed by a number
+// of nanoseconds, which is always positive (so that 1.5s before `UNIX_EPOCH` is encoded as -2s
+// and 500,000,000ns).
+#[cfg(feature = "std")]
+impl Encode for SystemTime {
+    type Error = Error;
+
+    fn fast_size(&self) -> usize {
+        0i64.fast_size() + 0u32.fast_size()
+    }
+
+    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        let (secs, nanos) = match self.duration_since(UNIX_EPOCH) {
+            Ok(since) => (i64::try_from(since.as_secs()).ok(), since.subsec_nanos()),
+            Err(err) => match err.duration() {
+                until if until.subsec_nanos() == 0 => (i64::try_from(until.as_secs()).ok().map(|secs| -secs), 0),
+                until => {
+                    (i64::try_from(until.as_secs() + 1).ok().map(|secs| -secs), 1_000_000_000 - until.subsec_nanos())
+                }
+            },
+        };
+
+        secs.ok_or_else(|| Error::invalid_data("time out of range"))?.encode_into(&mut writer)?;
+        nanos.encode_into(writer)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Decode for SystemTime {
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+        let ((secs, nanos), len) = <(i64, u32)>::decode_with_read_from(reader)?;
+        if nanos >= 1_000_000_000 {
+            return Err(Error::invalid_data("invalid time"));
+        }
+
+        let time = if secs >= 0 {
+            UNIX_EPOCH.checked_add(Duration::new(secs as u64, nanos))
+        } else {
+            UNIX_EPOCH
+                .checked_sub(Duration::from_secs(secs.unsigned_abs()))
+                .and_then(|time| time.checked_add(Duration::from_nanos(nanos as u64)))
+        };
+
+        match time {
+            Some(time) => Ok((time, len)),
+            None => Err(Error::invalid_data("time out of range")),
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                     impl Schema for ..                                     │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Schema for Duration {
+    fn schema() -> Layout {
+        Layout::Struct {
+            name: "Duration",
+            fields: Vec::from([
+                Field { name: "secs", layout: u64::schema() },
+                Field { name: "nanos", layout: u32::schema() },
+            ]),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Schema for SystemTime {
+    fn schema() -> Layout {
+        Layout::Struct {
+            name: "SystemTime",
+            fields: Vec::from([
+                Field { name: "secs", layout: i64::schema() },
+                Field { name: "nanos", layout: u32::schema() },
+            ]),
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                         owned!(..)                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+owned!(Duration);
+
+#[cfg(feature = "std")]
+owned!(SystemTime);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(all(test, feature = "std"))]
+#[test]
+fn times() {
+    use crate::ErrorKind;
+
+    let duration = Duration::new(3, 500_000_000);
+    let encoded = duration.encode().unwrap();
+    assert_eq!(encoded, [3, 0, 0, 0, 0, 0, 0, 0, 0, 0x65, 0xcd, 0x1d]);
+    assert_eq!(Duration::decode(&encoded).unwrap(), duration);
+
+    let invalid = |err: Error| matches!(err.kind(), ErrorKind::InvalidData { .. });
+    assert!(invalid(Duration::decode(&(u64::MAX, 1_000_000_000u32).encode().unwrap()).unwrap_err()));
+
+    for time in [UNIX_EPOCH + duration, UNIX_EPOCH - duration, UNIX_EPOCH - Duration::from_secs(2), SystemTime::now()] {
+        assert_eq!(SystemTime::decode(&time.encode().unwrap()).unwrap(), time);
+    }
+
+    assert_eq!((UNIX_EPOCH - duration).encode().unwrap(), (-4i64, 500_000_000u32).encode().unwrap());
+    assert!(invalid(SystemTime::decode(&(0i64, 1_000_000_000u32).encode().unwrap()).unwrap_err()));
+}
@@ -10,8 +10,13 @@
  * │                                          Imports                                           │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
-use crate::{Decode, DecodeRef, Encode};
-use std::io::{self, Read, Write};
+use crate::io::{Read, Write};
+use crate::limits::{self, Depth};
+use crate::prefix::{self, Collection, DecodeCollection, Prefix, U16};
+use crate::schema::{Layout, Schema};
+use crate::{Decode, DecodeBorrow, Encode, Error, Segment, Trace};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
 
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
  * │                                impl {En,De}code for Vec<T>                                 │ *
@@ -20,67 +25,90 @@ use std::io::{self, Read, Write};
 impl<T> Encode for Vec<T>
 where
     T: Encode,
-    T::Error: From<io::Error>,
+    T::Error: From<Error> + Trace,
 {
     type Error = T::Error;
 
-    default fn size(&self) -> Result<usize, Self::Error> {
-        if self.len() > u16::MAX as usize {
-            return Err(io::Error::new(io::ErrorKind::InvalidInput, "vec.len() > u16::MAX").into());
-        }
+    fn size(&self) -> Result<usize, Self::Error> {
+        prefix::size::<U16, _>(self)
+    }
 
-        let mut size = (self.len() as u16).fast_size();
-        for elem in self {
-            size += elem.size()?;
-        }
+    fn fast_size(&self) -> usize {
+        prefix::fast_size::<U16, _>(self)
+    }
 
-        Ok(size)
+    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+        prefix::encode_into::<U16, _, _>(self, writer)
     }
+}
 
-    default fn fast_size(&self) -> usize {
-        if self.len() > u16::MAX as usize {
-            0
-        } else {
-            (self.len() as u16).fast_size() + self.get(0).map(|elem| elem.fast_size() * self.len()).unwrap_or(0)
-        }
+impl<T> Decode for Vec<T>
+where
+    T: Decode,
+    T::Error: From<Error> + Trace,
+{
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+        prefix::decode_with_read_from::<U16, _, _>(reader)
     }
+}
 
-    default fn encode(&self) -> Result<Vec<u8>, Self::Error> {
-        let mut buf = Vec::with_capacity(self.size()?);
-        self.encode_into(&mut buf)?;
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                            impl {Decode,}Collection for Vec<T>                             │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
-        Ok(buf)
+impl<T> Collection for Vec<T>
+where
+    T: Encode,
+    T::Error: From<Error> + Trace,
+{
+    type Error = T::Error;
+
+    fn len(&self) -> usize {
+        Vec::len(self)
     }
 
-    default fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
-        if self.len() > u16::MAX as usize {
-            Err(io::Error::new(io::ErrorKind::InvalidInput, "vec.len() > u16::MAX").into())
-        } else {
-            (self.len() as u16).encode_into(&mut writer)?;
-            for elem in self {
-                elem.encode_into(&mut writer)?;
-            }
+    fn elems_size(&self) -> Result<usize, Self::Error> {
+        T::slice_size(self)
+    }
 
-            Ok(())
-        }
+    fn elems_fast_size(&self) -> usize {
+        self.first().map(|elem| elem.fast_size() * self.len()).unwrap_or(0)
+    }
+
+    fn encode_elems_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+        T::encode_slice_into(self, writer)
     }
 }
 
-impl<T> Decode for Vec<T>
+impl<T> DecodeCollection for Vec<T>
 where
     T: Decode,
-    T::Error: From<io::Error>,
+    T::Error: From<Error> + Trace,
 {
-    default fn decode_with_read(buf: &[u8]) -> Result<(Self, usize), Self::Error> {
-        Self::decode_with_read_from(buf)
+    fn decode_elems_with_read_from<R: Read>(len: usize, reader: R) -> Result<(Self, usize), Self::Error> {
+        T::decode_vec_from(len, reader)
     }
+}
 
-    default fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
-        let (len, mut read) = u16::decode_with_read_from(&mut reader)?;
-        let mut elems = Vec::with_capacity(len as usize);
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                impl DecodeBorrow for Vec<T>                                │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
-        for _ in 0..len {
-            let (elem, readb) = T::decode_with_read_from(&mut reader)?;
+impl<'de, T> DecodeBorrow<'de> for Vec<T>
+where
+    T: DecodeBorrow<'de>,
+    T::Error: From<Error> + Trace,
+{
+    fn decode_borrow_with_read(buf: &'de [u8]) -> Result<(Self, usize), Self::Error> {
+        let _depth = Depth::enter()?;
+        let (len, mut read) = U16::decode_with_read_from(buf)?;
+        limits::len(len)?;
+        limits::alloc::<T>(len)?;
+
+        let mut elems = Vec::with_capacity(limits::capacity::<T>(len));
+        for idx in 0..len {
+            let (elem, readb) = T::decode_borrow_with_read(&buf[read..])
+                .map_err(|err| err.trace(Some(Segment::Index(idx)), read))?;
             elems.push(elem);
             read += readb;
         }
@@ -90,38 +118,11 @@ where
 }
 
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
- * │                                impl {En,De}code for Vec<u8>                                │ *
+ * │                                   impl Schema for Vec<T>                                   │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
-impl Encode for Vec<u8> {
-    fn size(&self) -> Result<usize, Self::Error> {
-        self.as_slice().size()
-    }
-
-    fn fast_size(&self) -> usize {
-        self.as_slice().fast_size()
-    }
-
-    fn encode(&self) -> Result<Vec<u8>, Self::Error> {
-        Ok(self.clone())
-    }
-
-    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
-        self.as_slice().encode_into(writer)
-    }
-}
-
-impl Decode for Vec<u8> {
-    fn decode_with_read(buf: &[u8]) -> Result<(Self, usize), Self::Error> {
-        let (buf, read) = <[u8]>::decode_ref_with_read(buf)?;
-        Ok((buf.to_vec(), read))
-    }
-
-    fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
-        let (len, read) = u16::decode_with_read_from(&mut reader)?;
-        let mut data = vec![0; len as usize];
-        reader.read_exact(&mut data)?;
-
-        Ok((data, read + len as usize))
+impl<T: Schema> Schema for Vec<T> {
+    fn schema() -> Layout {
+        Layout::Seq { prefix: Box::new(U16::schema()), elem: Box::new(T::schema()) }
     }
 }
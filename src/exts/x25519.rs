@@ -10,8 +10,10 @@
  * │                                          Imports                                           │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
-use crate::{Decode, Encode};
-use std::io::{self, Read, Write};
+use crate::borrow::owned;
+use crate::io::{Read, Write};
+use crate::schema::{Layout, Schema};
+use crate::{Decode, Encode, Error};
 use x25519::PublicKey;
 
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
@@ -19,7 +21,7 @@ use x25519::PublicKey;
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
 impl Encode for PublicKey {
-    type Error = io::Error;
+    type Error = Error;
 
     fn fast_size(&self) -> usize {
         self.to_bytes().fast_size()
@@ -41,3 +43,19 @@ impl Decode for PublicKey {
         Ok((PublicKey::from(bytes), read))
     }
 }
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                 impl Schema for PublicKey                                  │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Schema for PublicKey {
+    fn schema() -> Layout {
+        <[u8; 32]>::schema()
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                         owned!(..)                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+owned!(PublicKey);
@@ -0,0 +1,77 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use crate::borrow::owned;
+use crate::io::{Read, Write};
+use crate::schema::{Layout, Schema};
+use crate::secret;
+use crate::{Decode, Encode, Error};
+use x25519::StaticSecret;
+use zeroize::Zeroizing;
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                             impl {En,De}code for StaticSecret                              │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Encode for StaticSecret {
+    type Error = Error;
+
+    fn fast_size(&self) -> usize {
+        32
+    }
+
+    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        let bytes = Zeroizing::new(self.to_bytes());
+        Ok(writer.write_all(&bytes[..])?)
+    }
+}
+
+impl Decode for StaticSecret {
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+        let bytes = secret::read(reader)?;
+        Ok((StaticSecret::from(*bytes), 32))
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                impl Schema for StaticSecret                                │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Schema for StaticSecret {
+    fn schema() -> Layout {
+        <[u8; 32]>::schema()
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                         owned!(..)                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+owned!(StaticSecret);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(test)]
+#[test]
+fn static_secret() {
+    use rand::rngs::OsRng;
+    use x25519::PublicKey;
+
+    let secret = StaticSecret::new(OsRng);
+    let encoded = crate::secret::encode(&secret).unwrap();
+    assert_eq!(&encoded[..], secret.to_bytes());
+
+    let decoded = StaticSecret::decode(&encoded).unwrap();
+    assert_eq!(PublicKey::from(&decoded), PublicKey::from(&secret));
+}
@@ -0,0 +1,293 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use crate::buf::Buffer;
+use crate::io::Write;
+use crate::prefix::{Prefix, U32};
+use crate::{Decode, Encode, Error, Incomplete};
+use alloc::vec::Vec;
+use cfg_if::cfg_if;
+use core::fmt::{self, Debug, Formatter};
+use core::marker::PhantomData;
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                       cfg_if! { .. }                                       │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+cfg_if! {
+    if #[cfg(feature = "tokio-util")] {
+        use std::io;
+        use tokio_util::bytes::{Buf, BufMut, BytesMut};
+        use tokio_util::codec::{Decoder, Encoder};
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                 struct FrameDecoder<T, P>                                  │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+pub const DEFAULT_MAX_LEN: usize = 8 * 1024 * 1024;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Decoded<T> {
+    Frame(T),
+    NeedMore(usize),
+}
+
+pub struct FrameDecoder<T, P = U32> {
+    buf: Buffer,
+    max_len: usize,
+    _marker: PhantomData<(T, P)>,
+}
+
+impl<T, P> FrameDecoder<T, P> {
+    pub fn new() -> Self {
+        FrameDecoder::with_max_len(DEFAULT_MAX_LEN)
+    }
+
+    pub fn with_max_len(max_len: usize) -> Self {
+        FrameDecoder { buf: Buffer::default(), max_len, _marker: PhantomData }
+    }
+
+    pub fn buffered(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn extend(&mut self, chunk: &[u8]) {
+        self.buf.extend_from_slice(chunk);
+    }
+}
+
+impl<T, P> FrameDecoder<T, P>
+where
+    T: Decode,
+    T::Error: From<Error> + Incomplete,
+    P: Prefix,
+{
+    pub fn decode(&mut self) -> Result<Decoded<T>, T::Error> {
+        match decode_frame::<P, T>(&self.buf, self.max_len)? {
+            Decoded::Frame((val, read)) => {
+                self.buf.consume(read);
+                Ok(Decoded::Frame(val))
+            }
+            Decoded::NeedMore(needed) => Ok(Decoded::NeedMore(needed)),
+        }
+    }
+}
+
+impl<T, P> Default for FrameDecoder<T, P> {
+    fn default() -> Self {
+        FrameDecoder::new()
+    }
+}
+
+// Implemented by hand since deriving them would require `T` and `P` to implement them too.
+impl<T, P> Clone for FrameDecoder<T, P> {
+    fn clone(&self) -> Self {
+        FrameDecoder { buf: self.buf.clone(), max_len: self.max_len, _marker: PhantomData }
+    }
+}
+
+impl<T, P> Debug for FrameDecoder<T, P> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("FrameDecoder").field("buffered", &self.buf.len()).field("max_len", &self.max_len).finish()
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                    fn {en,de}code_frame                                    │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+pub fn encode_frame<P, T>(val: &T) -> Result<Vec<u8>, T::Error>
+where
+    P: Prefix,
+    T: Encode + ?Sized,
+    T::Error: From<Error>,
+{
+    let size = val.size()?;
+    let mut buf = Vec::with_capacity(P::fast_size(size) + size);
+    encode_frame_into::<P, T, _>(val, &mut buf)?;
+
+    Ok(buf)
+}
+
+pub fn encode_frame_into<P, T, W>(val: &T, mut writer: W) -> Result<(), T::Error>
+where
+    P: Prefix,
+    T: Encode + ?Sized,
+    T::Error: From<Error>,
+    W: Write,
+{
+    P::encode_into(val.size()?, &mut writer)?;
+    val.encode_into(writer)
+}
+
+// Decodes the frame at the start of `buf`, returning the decoded value and the length of the
+// whole frame (header included).
+pub fn decode_frame<P, T>(buf: &[u8], max_len: usize) -> Result<Decoded<(T, usize)>, T::Error>
+where
+    P: Prefix,
+    T: Decode,
+    T::Error: From<Error> + Incomplete,
+{
+    let (len, header) = match P::decode_with_read_from(buf) {
+        Ok(decoded) => decoded,
+        Err(err) => return err.needed().map(Decoded::NeedMore).ok_or_else(|| err.into()),
+    };
+
+    if len > max_len {
+        return Err(Error::length_overflow(max_len as u64, len as u64).into());
+    } else if buf.len() - header < len {
+        return Ok(Decoded::NeedMore(len - (buf.len() - header)));
+    }
+
+    let (val, read) = T::decode_body(&buf[header..(header + len)])?;
+    if read != len {
+        return Err(Error::invalid_data("trailing bytes in frame").into());
+    }
+
+    Ok(Decoded::Frame((val, header + len)))
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                     struct Codec<T, P>                                     │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+cfg_if! {
+    if #[cfg(feature = "tokio-util")] {
+        pub struct Codec<T, P = U32> {
+            max_len: usize,
+            _marker: PhantomData<(T, P)>,
+        }
+
+        impl<T, P> Codec<T, P> {
+            pub fn new() -> Self {
+                Codec::with_max_len(DEFAULT_MAX_LEN)
+            }
+
+            pub fn with_max_len(max_len: usize) -> Self {
+                Codec { max_len, _marker: PhantomData }
+            }
+        }
+
+        impl<T, P> Default for Codec<T, P> {
+            fn default() -> Self {
+                Codec::new()
+            }
+        }
+
+        // Implemented by hand for the same reason as for `FrameDecoder`.
+        impl<T, P> Clone for Codec<T, P> {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl<T, P> Copy for Codec<T, P> {}
+
+        impl<T, P> Debug for Codec<T, P> {
+            fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+                f.debug_struct("Codec").field("max_len", &self.max_len).finish()
+            }
+        }
+
+        impl<T, P> Decoder for Codec<T, P>
+        where
+            T: Decode,
+            T::Error: From<Error> + From<io::Error> + Incomplete,
+            P: Prefix,
+        {
+            type Item = T;
+            type Error = T::Error;
+
+            fn decode(&mut self, src: &mut BytesMut) -> Result<Option<T>, T::Error> {
+                match decode_frame::<P, T>(src, self.max_len)? {
+                    Decoded::Frame((val, read)) => {
+                        src.advance(read);
+                        Ok(Some(val))
+                    }
+                    Decoded::NeedMore(needed) => {
+                        src.reserve(needed);
+                        Ok(None)
+                    }
+                }
+            }
+        }
+
+        impl<T, P> Encoder<T> for Codec<T, P>
+        where
+            T: Encode,
+            T::Error: From<Error> + From<io::Error>,
+            P: Prefix,
+        {
+            type Error = T::Error;
+
+            fn encode(&mut self, val: T, dst: &mut BytesMut) -> Result<(), T::Error> {
+                let size = val.size()?;
+                dst.reserve(P::fast_size(size) + size);
+                encode_frame_into::<P, T, _>(&val, dst.writer())
+            }
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(test)]
+#[test]
+fn frames() {
+    use crate::prefix::Varint;
+
+    let mut stream = encode_frame::<U32, _>(&(1u8, 2u16)).unwrap();
+    assert_eq!(stream, [3, 0, 0, 0, 1, 2, 0]);
+    encode_frame_into::<U32, _, _>(&(3u8, 4u16), &mut stream).unwrap();
+
+    let mut decoder = FrameDecoder::<(u8, u16)>::new();
+    assert_eq!(decoder.decode().unwrap(), Decoded::NeedMore(4));
+    decoder.extend(&stream[..2]);
+    assert_eq!(decoder.decode().unwrap(), Decoded::NeedMore(2));
+    decoder.extend(&stream[2..6]);
+    assert_eq!(decoder.decode().unwrap(), Decoded::NeedMore(1));
+    decoder.extend(&stream[6..]);
+    assert_eq!(decoder.decode().unwrap(), Decoded::Frame((1, 2)));
+    assert_eq!(decoder.decode().unwrap(), Decoded::Frame((3, 4)));
+    assert_eq!(decoder.decode().unwrap(), Decoded::NeedMore(4));
+    assert_eq!(decoder.buffered(), 0);
+
+    let mut decoder = FrameDecoder::<u8, Varint>::with_max_len(1);
+    decoder.extend(&[2, 0, 0]);
+    assert!(decoder.decode().is_err());
+
+    let mut decoder = FrameDecoder::<u8, Varint>::new();
+    decoder.extend(&[2, 0, 0]);
+    assert!(decoder.decode().is_err());
+}
+
+#[cfg(all(test, feature = "tokio-util"))]
+#[test]
+fn codec() {
+    // Doesn't require `T` to be `Copy`.
+    let mut codec = Codec::<String>::new();
+    let _copy = codec;
+    let mut buf = BytesMut::new();
+    codec.encode("foo".to_owned(), &mut buf).unwrap();
+    codec.encode("bar".to_owned(), &mut buf).unwrap();
+
+    let mut partial = buf.split_to(4);
+    assert_eq!(codec.decode(&mut partial).unwrap(), None);
+    partial.unsplit(buf);
+    assert_eq!(codec.decode(&mut partial).unwrap().as_deref(), Some("foo"));
+    assert_eq!(codec.decode(&mut partial).unwrap().as_deref(), Some("bar"));
+    assert_eq!(codec.decode(&mut partial).unwrap(), None);
+}
@@ -0,0 +1,166 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use cfg_if::cfg_if;
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                       cfg_if! { .. }                                       │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+cfg_if! {
+    if #[cfg(feature = "std")] {
+        pub use std::io::{Error, ErrorKind, Read, Result, Write};
+    } else {
+        use alloc::boxed::Box;
+        use alloc::vec::Vec;
+        use core::fmt::{self, Display, Formatter};
+        use core::mem;
+
+        // Minimal stand-ins for `std::io`'s types, covering only what this crate needs.
+
+        pub type Result<T> = core::result::Result<T, Error>;
+
+        type Custom = Box<dyn core::error::Error + Send + Sync>;
+
+        #[derive(Debug)]
+        pub struct Error {
+            kind: ErrorKind,
+            inner: Option<Custom>,
+        }
+
+        #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+        #[non_exhaustive]
+        pub enum ErrorKind {
+            InvalidInput,
+            InvalidData,
+            Interrupted,
+            UnexpectedEof,
+            WriteZero,
+            Other,
+        }
+
+        pub trait Read {
+            fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+        }
+
+        pub trait Write {
+            fn write(&mut self, buf: &[u8]) -> Result<usize>;
+
+            fn write_all(&mut self, mut buf: &[u8]) -> Result<()> {
+                while !buf.is_empty() {
+                    match self.write(buf) {
+                        Ok(0) => return Err(Error::from(ErrorKind::WriteZero)),
+                        Ok(written) => buf = &buf[written..],
+                        Err(err) if err.kind() == ErrorKind::Interrupted => (),
+                        Err(err) => return Err(err),
+                    }
+                }
+
+                Ok(())
+            }
+        }
+
+        impl Error {
+            pub fn new<E: Into<Custom>>(kind: ErrorKind, inner: E) -> Self {
+                Error { kind, inner: Some(inner.into()) }
+            }
+
+            pub fn kind(&self) -> ErrorKind {
+                self.kind
+            }
+
+            pub fn get_ref(&self) -> Option<&(dyn core::error::Error + Send + Sync + 'static)> {
+                self.inner.as_deref()
+            }
+
+            pub fn into_inner(self) -> Option<Custom> {
+                self.inner
+            }
+        }
+
+        impl From<ErrorKind> for Error {
+            fn from(kind: ErrorKind) -> Self {
+                Error { kind, inner: None }
+            }
+        }
+
+        impl Display for Error {
+            fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+                match &self.inner {
+                    Some(inner) => write!(f, "{}", inner),
+                    None => write!(f, "{:?}", self.kind),
+                }
+            }
+        }
+
+        impl core::error::Error for Error {}
+
+        impl Read for &[u8] {
+            fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+                let len = buf.len().min(self.len());
+                let (head, tail) = self.split_at(len);
+                buf[..len].copy_from_slice(head);
+                *self = tail;
+
+                Ok(len)
+            }
+        }
+
+        impl<R: Read + ?Sized> Read for &mut R {
+            fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+                (**self).read(buf)
+            }
+        }
+
+        impl Write for Vec<u8> {
+            fn write(&mut self, buf: &[u8]) -> Result<usize> {
+                self.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+        }
+
+        impl Write for &mut [u8] {
+            fn write(&mut self, buf: &[u8]) -> Result<usize> {
+                let len = buf.len().min(self.len());
+                let (head, tail) = mem::take(self).split_at_mut(len);
+                head.copy_from_slice(&buf[..len]);
+                *self = tail;
+
+                Ok(len)
+            }
+        }
+
+        impl<W: Write + ?Sized> Write for &mut W {
+            fn write(&mut self, buf: &[u8]) -> Result<usize> {
+                (**self).write(buf)
+            }
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(all(test, not(feature = "std")))]
+#[test]
+fn slices() {
+    use crate::{Decode, Encode};
+
+    let mut buf = [0; 4];
+    (1u16, 2u8).encode_into(&mut buf[..]).unwrap();
+    assert_eq!(buf, [1, 0, 2, 0]);
+    assert_eq!(<(u16, u8)>::decode_from(&buf[..]).unwrap(), (1, 2));
+
+    let err = (1u32, 2u8).encode_into(&mut buf[..]).unwrap_err();
+    assert!(matches!(err.kind(), crate::ErrorKind::Io(err) if err.kind() == ErrorKind::WriteZero));
+}
@@ -10,16 +10,86 @@
  * │                                       Configuration                                        │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
-#![feature(array_map, maybe_uninit_uninit_array, min_const_generics, min_specialization)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
  * │                                          Imports                                           │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
+mod buf;
+mod error;
 mod exts;
-
+pub mod borrow;
+pub mod canonical;
+pub mod dump;
+pub mod endian;
+pub mod envelope;
+pub mod frame;
+pub mod io;
+pub mod limits;
+pub mod prefix;
+pub mod schema;
+pub mod tagged;
+pub mod varint;
+
+#[cfg(test)]
+mod consistency;
+
+pub use crate::borrow::DecodeBorrow;
+pub use crate::error::{Error, ErrorKind, Incomplete, Segment, Trace};
+pub use crate::schema::Schema;
+
+use crate::io::{Read, Write};
+use crate::limits::Depth;
+use crate::prefix::{Collection, DecodeCollection, U16};
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use cfg_if::cfg_if;
 use core::mem::{self, MaybeUninit};
-use std::io::{self, Read, Write};
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                       cfg_if! { .. }                                       │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+cfg_if! {
+    if #[cfg(feature = "derive")] {
+        pub use derive::{Decode, DecodeBorrow, Encode, Schema};
+    }
+}
+
+cfg_if! {
+    if #[cfg(feature = "ed25519")] {
+        pub mod signed;
+    }
+}
+
+cfg_if! {
+    if #[cfg(feature = "sealed")] {
+        pub mod sealed;
+    }
+}
+
+cfg_if! {
+    if #[cfg(all(feature = "secrets", any(feature = "ed25519", feature = "x25519")))] {
+        pub mod secret;
+    }
+}
+
+cfg_if! {
+    if #[cfg(feature = "serde")] {
+        pub mod serde;
+    }
+}
+
+cfg_if! {
+    if #[cfg(feature = "tokio")] {
+        pub mod aio;
+    }
+}
 
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
  * │                                        trait Encode                                        │ *
@@ -42,6 +112,33 @@ pub trait Encode {
     }
 
     fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error>;
+
+    // Used by `[Self; _]` and `Vec<Self>` so that types like `u8` can encode whole slices at once
+    // without relying on specialization.
+    #[doc(hidden)]
+    fn slice_size(slice: &[Self]) -> Result<usize, Self::Error>
+    where
+        Self: Sized,
+    {
+        let mut size = 0;
+        for elem in slice {
+            size += elem.size()?;
+        }
+
+        Ok(size)
+    }
+
+    #[doc(hidden)]
+    fn encode_slice_into<W: Write>(slice: &[Self], mut writer: W) -> Result<(), Self::Error>
+    where
+        Self: Sized,
+    {
+        for elem in slice {
+            elem.encode_into(&mut writer)?;
+        }
+
+        Ok(())
+    }
 }
 
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
@@ -62,6 +159,52 @@ pub trait Decode: Encode + Sized {
     }
 
     fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error>;
+
+    // Used by `Envelope` and frames, whose body is exactly the encoding of `Self`: derived types
+    // only default their trailing `#[f0rm47(default)]` fields when decoded through it, since only
+    // then does the input ending before them mean that they were never written.
+    #[doc(hidden)]
+    fn decode_body(body: &[u8]) -> Result<(Self, usize), Self::Error> {
+        Self::decode_with_read(body)
+    }
+
+    // Used by `[Self; _]` and `Vec<Self>`, see `Encode::slice_size()`.
+    #[doc(hidden)]
+    fn decode_array_from<R: Read, const LEN: usize>(mut reader: R) -> Result<([Self; LEN], usize), Self::Error>
+    where
+        Self::Error: Trace,
+    {
+        let mut read = 0;
+        let mut arr = [const { MaybeUninit::<Self>::uninit() }; LEN];
+
+        for (idx, elem) in arr.iter_mut().enumerate() {
+            let (decoded, readb) =
+                Self::decode_with_read_from(&mut reader).map_err(|err| err.trace(Some(Segment::Index(idx)), read))?;
+            *elem = MaybeUninit::new(decoded);
+            read += readb;
+        }
+
+        Ok((arr.map(|elem| unsafe { elem.assume_init() }), read))
+    }
+
+    #[doc(hidden)]
+    fn decode_vec_from<R: Read>(len: usize, mut reader: R) -> Result<(Vec<Self>, usize), Self::Error>
+    where
+        Self::Error: From<Error> + Trace,
+    {
+        limits::alloc::<Self>(len)?;
+        let mut read = 0;
+        let mut elems = Vec::with_capacity(limits::capacity::<Self>(len));
+
+        for idx in 0..len {
+            let (elem, readb) =
+                Self::decode_with_read_from(&mut reader).map_err(|err| err.trace(Some(Segment::Index(idx)), read))?;
+            elems.push(elem);
+            read += readb;
+        }
+
+        Ok((elems, read))
+    }
 }
 
 pub trait DecodeRef: Encode {
@@ -120,21 +263,28 @@ impl<T: Encode + ?Sized> Encode for Box<T> {
     }
 }
 
-impl<T: Decode + ?Sized> Decode for Box<T> {
+impl<T: Decode> Decode for Box<T>
+where
+    T::Error: From<Error>,
+{
     fn decode(buf: &[u8]) -> Result<Self, Self::Error> {
+        let _depth = Depth::enter()?;
         T::decode(buf).map(Box::new)
     }
 
     fn decode_from<R: Read>(reader: R) -> Result<Self, Self::Error> {
+        let _depth = Depth::enter()?;
         T::decode_from(reader).map(Box::new)
     }
 
     fn decode_with_read(buf: &[u8]) -> Result<(Self, usize), Self::Error> {
+        let _depth = Depth::enter()?;
         let (val, len) = T::decode_with_read(buf)?;
         Ok((Box::new(val), len))
     }
 
     fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+        let _depth = Depth::enter()?;
         let (val, len) = T::decode_with_read_from(reader)?;
         Ok((Box::new(val), len))
     }
@@ -146,7 +296,7 @@ impl<T: Decode + ?Sized> Decode for Box<T> {
 
 impl<T: Encode> Encode for Option<T>
 where
-    T::Error: From<io::Error>,
+    T::Error: From<Error> + Trace,
 {
     type Error = T::Error;
 
@@ -179,12 +329,13 @@ where
 
 impl<T: Decode> Decode for Option<T>
 where
-    T::Error: From<io::Error>,
+    T::Error: From<Error> + Trace,
 {
     fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
         match bool::decode_with_read_from(&mut reader)? {
             (true, read1) => {
-                let (val, read2) = T::decode_with_read_from(reader)?;
+                let _depth = Depth::enter()?;
+                let (val, read2) = T::decode_with_read_from(reader).map_err(|err| err.trace(None, read1))?;
                 Ok((Some(val), read1 + read2))
             }
             (false, read) => Ok((None, read))
@@ -192,6 +343,58 @@ where
     }
 }
 
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                             impl {En,De}code for Result<T, E>                              │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// `Ok` is tagged with `0u8` and `Err` with `1u8`.
+impl<Err, T, E> Encode for Result<T, E>
+where
+    T: Encode<Error = Err>,
+    E: Encode<Error = Err>,
+    Err: From<Error>,
+{
+    type Error = Err;
+
+    fn size(&self) -> Result<usize, Self::Error> {
+        match self {
+            Ok(val) => tagged::variant_size(0u8, val),
+            Err(err) => tagged::variant_size(1u8, err),
+        }
+    }
+
+    fn fast_size(&self) -> usize {
+        match self {
+            Ok(val) => tagged::variant_fast_size(0u8, val),
+            Err(err) => tagged::variant_fast_size(1u8, err),
+        }
+    }
+
+    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+        match self {
+            Ok(val) => tagged::encode_variant(0u8, val, writer),
+            Err(err) => tagged::encode_variant(1u8, err, writer),
+        }
+    }
+}
+
+impl<Err, T, E> Decode for Result<T, E>
+where
+    T: Encode<Error = Err> + Decode,
+    E: Encode<Error = Err> + Decode,
+    Err: From<Error> + Trace,
+{
+    fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
+        let (tag, read) = u8::decode_with_read_from(&mut reader)?;
+        let _depth = Depth::enter()?;
+        match tag {
+            0 => tagged::decode_variant(read, reader, Ok),
+            1 => tagged::decode_variant(read, reader, Err),
+            _ => Err(Error::invalid_tag("Result", tag as u64).into()),
+        }
+    }
+}
+
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
  * │                                           Macros                                           │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
@@ -199,14 +402,14 @@ where
 macro_rules! primitive {
     ($primitive:ty) => {
         impl Encode for $primitive {
-            type Error = io::Error;
+            type Error = Error;
 
             fn fast_size(&self) -> usize {
                 mem::size_of::<$primitive>()
             }
 
             fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
-                writer.write_all(&self.to_le_bytes())
+                Ok(writer.write_all(&self.to_le_bytes())?)
             }
         }
 
@@ -249,13 +452,14 @@ macro_rules! tuple {
         impl<Err, $($name),+> Decode for ($($name),+)
         where
             $($name: Encode<Error = Err> + Decode,)+
+            Err: Trace,
         {
-            #[allow(clippy::eval_order_dependence)]
             fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
                 let mut len = 0;
                 let val = (
                     $({
-                        let (val, read) = <$name>::decode_with_read_from(&mut reader)?;
+                        let (val, read) = <$name>::decode_with_read_from(&mut reader)
+                            .map_err(|err| err.trace(Some(Segment::Index($idx)), len))?;
                         len += read;
                         val
                     },)+
@@ -290,7 +494,6 @@ macro_rules! tuples {
  * │                                       primitive!(..)                                       │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
-primitive!(u8);
 primitive!(u16);
 primitive!(u32);
 primitive!(u64);
@@ -302,18 +505,71 @@ primitive!(i32);
 primitive!(i64);
 primitive!(i128);
 
+primitive!(f32);
+primitive!(f64);
+
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
  * │                                        tuples!(..)                                         │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
 tuples!(0: A, 1: B, 2: C, 3: D, 4: E, 5: F, 6: G, 7: H, 8: I, 9: J, 10: K, 11: L, 12: M);
 
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                  impl {En,De}code for u8                                   │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Encode for u8 {
+    type Error = Error;
+
+    fn fast_size(&self) -> usize {
+        1
+    }
+
+    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        Ok(writer.write_all(&[*self])?)
+    }
+
+    fn slice_size(slice: &[Self]) -> Result<usize, Self::Error> {
+        Ok(slice.len())
+    }
+
+    fn encode_slice_into<W: Write>(slice: &[Self], mut writer: W) -> Result<(), Self::Error> {
+        Ok(writer.write_all(slice)?)
+    }
+}
+
+impl Decode for u8 {
+    fn decode_with_read(buf: &[u8]) -> Result<(Self, usize), Self::Error> {
+        match buf.first() {
+            Some(byte) => Ok((*byte, 1)),
+            None => Err(Error::unexpected_eof(1)),
+        }
+    }
+
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+        let ([byte], read) = Self::decode_array_from(reader)?;
+        Ok((byte, read))
+    }
+
+    fn decode_array_from<R: Read, const LEN: usize>(reader: R) -> Result<([Self; LEN], usize), Self::Error> {
+        let mut bytes = [0; LEN];
+        error::read_exact(reader, &mut bytes)?;
+
+        Ok((bytes, LEN))
+    }
+
+    fn decode_vec_from<R: Read>(len: usize, reader: R) -> Result<(Vec<Self>, usize), Self::Error> {
+        limits::alloc::<u8>(len)?;
+        Ok((limits::read_vec(reader, len)?, len))
+    }
+}
+
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
  * │                                 impl {En,De}code for bool                                  │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
 impl Encode for bool {
-    type Error = io::Error;
+    type Error = Error;
 
     fn fast_size(&self) -> usize {
         (*self as u8).fast_size()
@@ -330,7 +586,7 @@ impl Decode for bool {
         match val {
             0 => Ok((false, len)),
             1 => Ok((true, len)),
-            _ => Err(io::Error::new(io::ErrorKind::InvalidData, "expected 0u8 or 1u8")),
+            tag => Err(Error::invalid_tag("bool", tag as u64)),
         }
     }
 
@@ -339,7 +595,7 @@ impl Decode for bool {
         match val {
             0 => Ok((false, len)),
             1 => Ok((true, len)),
-            _ => Err(io::Error::new(io::ErrorKind::InvalidData, "expected 0u8 or 1u8")),
+            tag => Err(Error::invalid_tag("bool", tag as u64)),
         }
     }
 }
@@ -349,7 +605,7 @@ impl Decode for bool {
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
 impl Encode for usize {
-    type Error = io::Error;
+    type Error = Error;
 
     fn fast_size(&self) -> usize {
         (*self as u64).fast_size()
@@ -361,7 +617,7 @@ impl Encode for usize {
 }
 
 impl Encode for isize {
-    type Error = io::Error;
+    type Error = Error;
 
     fn fast_size(&self) -> usize {
         (*self as i64).fast_size()
@@ -396,6 +652,33 @@ impl Decode for isize {
     }
 }
 
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                 impl {En,De}code for char                                  │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Encode for char {
+    type Error = Error;
+
+    fn fast_size(&self) -> usize {
+        (*self as u32).fast_size()
+    }
+
+    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+        (*self as u32).encode_into(writer)
+    }
+}
+
+impl Decode for char {
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+        let (val, len) = u32::decode_with_read_from(reader)?;
+        if let Some(chr) = char::from_u32(val) {
+            Ok((chr, len))
+        } else {
+            Err(Error::invalid_data("invalid char"))
+        }
+    }
+}
+
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
  * │                                impl {En,De}code for [T; _]                                 │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
@@ -406,129 +689,203 @@ where
 {
     type Error = T::Error;
 
-    default fn size(&self) -> Result<usize, Self::Error> {
-        let mut size = 0;
-        for elem in self {
-            size += elem.size()?;
-        }
-
-        Ok(size)
-    }
-
-    default fn fast_size(&self) -> usize {
-        self[0].fast_size() * LEN
+    fn size(&self) -> Result<usize, Self::Error> {
+        T::slice_size(self)
     }
 
-    default fn encode(&self) -> Result<Vec<u8>, Self::Error> {
-        let mut buf = Vec::with_capacity(self.size()?);
-        self.encode_into(&mut buf)?;
-
-        Ok(buf)
+    fn fast_size(&self) -> usize {
+        self.first().map(|elem| elem.fast_size() * LEN).unwrap_or(0)
     }
 
-    default fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
-        for elem in self {
-            elem.encode_into(&mut writer)?;
-        }
-
-        Ok(())
+    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+        T::encode_slice_into(self, writer)
     }
 }
 
 impl<T, const LEN: usize> Decode for [T; LEN]
 where
     T: Decode,
+    T::Error: Trace,
 {
-    default fn decode_with_read(buf: &[u8]) -> Result<(Self, usize), Self::Error> {
-        Self::decode_with_read_from(buf)
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+        T::decode_array_from(reader)
     }
+}
 
-    default fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
-        let mut read = 0;
-        let mut arr = MaybeUninit::uninit_array();
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                              impl {Encode,DecodeRef} for [u8]                              │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
-        for elem in &mut arr[..] {
-            let (decoded, readb) = T::decode_with_read_from(&mut reader)?;
-            *elem = MaybeUninit::new(decoded);
-            read += readb;
+impl Encode for [u8] {
+    type Error = Error;
+
+    fn fast_size(&self) -> usize {
+        prefix::fast_size::<U16, _>(self)
+    }
+
+    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+        prefix::encode_into::<U16, _, _>(self, writer)
+    }
+}
+
+impl DecodeRef for [u8] {
+    fn decode_ref_with_read(buf: &[u8]) -> Result<(&Self, usize), Self::Error> {
+        let (len, read) = u16::decode_with_read(buf)?;
+        let len = len as usize;
+
+        if buf.len() < len + read {
+            Err(Error::unexpected_eof(len + read - buf.len()).trace(None, read))
+        } else {
+            Ok((&buf[read..(len + read)], len + read))
         }
+    }
+}
 
-        Ok((arr.map(|elem| unsafe { elem.assume_init() }), read))
+impl Collection for [u8] {
+    type Error = Error;
+
+    fn len(&self) -> usize {
+        <[u8]>::len(self)
+    }
+
+    fn elems_size(&self) -> Result<usize, Self::Error> {
+        Ok(self.len())
+    }
+
+    fn elems_fast_size(&self) -> usize {
+        self.len()
+    }
+
+    fn encode_elems_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        Ok(writer.write_all(self)?)
     }
 }
 
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
- * │                                impl {En,De}code for [u8; _]                                │ *
+ * │                              impl {Encode,DecodeRef} for str                               │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
-impl<const LEN: usize> Encode for [u8; LEN] {
-    fn size(&self) -> Result<usize, Self::Error> {
-        Ok(LEN)
-    }
+impl Encode for str {
+    type Error = Error;
 
     fn fast_size(&self) -> usize {
-        LEN
+        prefix::fast_size::<U16, _>(self)
     }
 
-    fn encode(&self) -> Result<Vec<u8>, Self::Error> {
-        Ok(self.to_vec())
+    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+        prefix::encode_into::<U16, _, _>(self, writer)
     }
+}
 
-    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
-        writer.write_all(self)
+impl DecodeRef for str {
+    fn decode_ref_with_read(buf: &[u8]) -> Result<(&Self, usize), Self::Error> {
+        let (buf, read) = <[u8]>::decode_ref_with_read(buf)?;
+        if let Ok(str) = core::str::from_utf8(buf) {
+            Ok((str, read))
+        } else {
+            Err(Error::invalid_data("invalid utf-8"))
+        }
     }
 }
 
-impl<const LEN: usize> Decode for [u8; LEN] {
-    fn decode_with_read(buf: &[u8]) -> Result<(Self, usize), Self::Error> {
-        if buf.len() < LEN {
-            Err(io::Error::new(io::ErrorKind::UnexpectedEof, "not enough data"))
-        } else {
-            let mut bytes = [0; LEN];
-            bytes[..].copy_from_slice(&buf[..LEN]);
+impl Collection for str {
+    type Error = Error;
 
-            Ok((bytes, LEN))
-        }
+    fn len(&self) -> usize {
+        str::len(self)
     }
 
-    fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
-        let mut bytes = [0; LEN];
-        reader.read_exact(&mut bytes)?;
+    fn elems_size(&self) -> Result<usize, Self::Error> {
+        Ok(self.len())
+    }
 
-        Ok((bytes, LEN))
+    fn elems_fast_size(&self) -> usize {
+        self.len()
+    }
+
+    fn encode_elems_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        Ok(writer.write_all(self.as_bytes())?)
     }
 }
 
 /* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
- * │                              impl {Encode,DecodeRef} for [u8]                              │ *
+ * │                                impl {En,De}code for String                                 │ *
 \* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
 
-impl Encode for [u8] {
-    type Error = io::Error;
+impl Encode for String {
+    type Error = Error;
 
     fn fast_size(&self) -> usize {
-        self.len() + (self.len() as u16).fast_size()
+        self.as_str().fast_size()
     }
 
-    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
-        if self.len() > u16::MAX as usize {
-            Err(io::Error::new(io::ErrorKind::InvalidInput, "buf.len() > u16::MAX"))
-        } else {
-            (self.len() as u16).encode_into(&mut writer)?;
-            writer.write_all(self)
-        }
+    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+        self.as_str().encode_into(writer)
     }
 }
 
-impl DecodeRef for [u8] {
-    fn decode_ref_with_read(buf: &[u8]) -> Result<(&Self, usize), Self::Error> {
-        let (len, read) = u16::decode_with_read(buf)?;
-        let len = len as usize;
+impl Decode for String {
+    fn decode_with_read(buf: &[u8]) -> Result<(Self, usize), Self::Error> {
+        let (str, read) = str::decode_ref_with_read(buf)?;
+        Ok((str.to_owned(), read))
+    }
 
-        if buf.len() < len + read {
-            Err(io::Error::new(io::ErrorKind::UnexpectedEof, "not enough data"))
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+        prefix::decode_with_read_from::<U16, _, _>(reader)
+    }
+}
+
+impl Collection for String {
+    type Error = Error;
+
+    fn len(&self) -> usize {
+        String::len(self)
+    }
+
+    fn elems_size(&self) -> Result<usize, Self::Error> {
+        Ok(self.len())
+    }
+
+    fn elems_fast_size(&self) -> usize {
+        self.len()
+    }
+
+    fn encode_elems_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+        self.as_str().encode_elems_into(writer)
+    }
+}
+
+impl DecodeCollection for String {
+    fn decode_elems_with_read_from<R: Read>(len: usize, reader: R) -> Result<(Self, usize), Self::Error> {
+        limits::alloc::<u8>(len)?;
+        let data = limits::read_vec(reader, len)?;
+
+        if let Ok(string) = String::from_utf8(data) {
+            Ok((string, len))
         } else {
-            Ok((&buf[read..(len + read)], len + read))
+            Err(Error::invalid_data("invalid utf-8"))
         }
     }
 }
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(test)]
+#[test]
+fn text() {
+    let encoded = "héllo".encode().unwrap();
+    assert_eq!(encoded, [6, 0, b'h', 0xc3, 0xa9, b'l', b'l', b'o']);
+    assert_eq!(str::decode_ref(&encoded).unwrap(), "héllo");
+    assert_eq!(String::decode(&encoded).unwrap(), "héllo");
+    assert_eq!(String::decode_from(&encoded[..]).unwrap(), "héllo");
+    assert_eq!(String::from("héllo").encode().unwrap(), encoded);
+
+    let invalid = [2, 0, 0xc3, 0x28];
+    assert!(matches!(str::decode_ref(&invalid).unwrap_err().kind(), ErrorKind::InvalidData { .. }));
+    assert!(matches!(String::decode_from(&invalid[..]).unwrap_err().kind(), ErrorKind::InvalidData { .. }));
+
+    assert_eq!(char::decode(&'é'.encode().unwrap()).unwrap(), 'é');
+    assert!(matches!(char::decode(&0xd800u32.encode().unwrap()).unwrap_err().kind(), ErrorKind::InvalidData { .. }));
+}
@@ -0,0 +1,328 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use crate::error::{self, ErrorKind};
+use crate::io::{self, Read};
+use crate::{Decode, Error};
+use alloc::vec::Vec;
+use cfg_if::cfg_if;
+use core::cell::Cell;
+use core::fmt::{self, Display, Formatter};
+use core::mem;
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                    struct DecodeLimits                                     │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Limits applied while decoding a single value with `DecodeLimits::decode*()`. `max_alloc` is
+// measured in bytes of collection elements, `max_depth` in nested boxes, options, collections
+// and derived types. Without `std`, only `max_bytes` can be set.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DecodeLimits {
+    pub max_bytes: usize,
+    pub max_len: usize,
+    pub max_depth: usize,
+    pub max_alloc: usize,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Limit {
+    Bytes,
+    Len,
+    Depth,
+    Alloc,
+}
+
+pub struct Depth {
+    entered: bool,
+}
+
+impl DecodeLimits {
+    pub fn decode<T: Decode>(&self, buf: &[u8]) -> Result<T, T::Error>
+    where
+        T::Error: From<Error>,
+    {
+        Ok(self.decode_with_read_from::<T, _>(buf)?.0)
+    }
+
+    pub fn decode_from<T: Decode, R: Read>(&self, reader: R) -> Result<T, T::Error>
+    where
+        T::Error: From<Error>,
+    {
+        Ok(self.decode_with_read_from::<T, _>(reader)?.0)
+    }
+
+    pub fn decode_with_read<T: Decode>(&self, buf: &[u8]) -> Result<(T, usize), T::Error>
+    where
+        T::Error: From<Error>,
+    {
+        self.decode_with_read_from(buf)
+    }
+
+    pub fn decode_with_read_from<T: Decode, R: Read>(&self, reader: R) -> Result<(T, usize), T::Error>
+    where
+        T::Error: From<Error>,
+    {
+        self.apply(|| T::decode_with_read_from(Limited { inner: reader, left: self.max_bytes, max: self.max_bytes }))
+    }
+
+    // Applies the limits to whatever `f` decodes, except for `max_bytes` which is up to the caller.
+    pub(crate) fn apply<T, E: From<Error>, F: FnOnce() -> Result<T, E>>(&self, f: F) -> Result<T, E> {
+        self.check()?;
+        let _scope = Scope::enter(State { limits: *self, depth: 0, alloc: 0 });
+        f()
+    }
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        DecodeLimits { max_bytes: usize::MAX, max_len: usize::MAX, max_depth: usize::MAX, max_alloc: usize::MAX }
+    }
+}
+
+impl Display for Limit {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Limit::Bytes => f.write_str("max_bytes"),
+            Limit::Len => f.write_str("max_len"),
+            Limit::Depth => f.write_str("max_depth"),
+            Limit::Alloc => f.write_str("max_alloc"),
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        static STATE                                        │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[derive(Clone, Copy)]
+struct State {
+    limits: DecodeLimits,
+    depth: usize,
+    alloc: usize,
+}
+
+struct Scope {
+    prev: Option<State>,
+}
+
+cfg_if! {
+    if #[cfg(feature = "std")] {
+        thread_local! {
+            static STATE: Cell<Option<State>> = const { Cell::new(None) };
+        }
+
+        impl DecodeLimits {
+            fn check(&self) -> Result<(), Error> {
+                Ok(())
+            }
+        }
+    } else {
+        // Without `std` there is no thread-local storage to keep the state in, so only `max_bytes`
+        // (which doesn't need any) can be enforced and setting any other limit is an error.
+        struct NoState;
+
+        impl NoState {
+            fn with<F: FnOnce(&Cell<Option<State>>) -> R, R>(&self, f: F) -> R {
+                f(&Cell::new(None))
+            }
+        }
+
+        static STATE: NoState = NoState;
+
+        impl DecodeLimits {
+            fn check(&self) -> Result<(), Error> {
+                if self.max_len != usize::MAX || self.max_depth != usize::MAX || self.max_alloc != usize::MAX {
+                    return Err(Error::new(ErrorKind::Unsupported {
+                        context: "only max_bytes can be limited without the std feature",
+                    }));
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Scope {
+    fn enter(state: State) -> Self {
+        Scope { prev: STATE.with(|cell| cell.replace(Some(state))) }
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        STATE.with(|cell| cell.set(self.prev));
+    }
+}
+
+pub(crate) fn exceeded(limit: Limit, max: usize) -> Error {
+    Error::new(ErrorKind::LimitExceeded { limit, max })
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        struct Depth                                        │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Depth {
+    pub fn enter() -> Result<Self, Error> {
+        STATE.with(|cell| match cell.get() {
+            Some(state) if state.depth >= state.limits.max_depth => {
+                Err(exceeded(Limit::Depth, state.limits.max_depth))
+            }
+            Some(mut state) => {
+                state.depth += 1;
+                cell.set(Some(state));
+                Ok(Depth { entered: true })
+            }
+            None => Ok(Depth { entered: false }),
+        })
+    }
+}
+
+impl Drop for Depth {
+    fn drop(&mut self) {
+        if self.entered {
+            STATE.with(|cell| {
+                if let Some(mut state) = cell.get() {
+                    state.depth -= 1;
+                    cell.set(Some(state));
+                }
+            });
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                         fn guards                                          │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Upper bound on the capacity reserved up front for a collection whose length was read from the
+// input, so that a bogus length can't trigger a large allocation before any element is decoded.
+pub(crate) const MAX_PREALLOC: usize = 64 * 1024;
+
+pub(crate) fn len(len: usize) -> Result<(), Error> {
+    match STATE.with(Cell::get) {
+        Some(state) if len > state.limits.max_len => Err(exceeded(Limit::Len, state.limits.max_len)),
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn alloc<T>(len: usize) -> Result<(), Error> {
+    STATE.with(|cell| match cell.get() {
+        Some(mut state) => {
+            state.alloc = state.alloc.saturating_add(len.saturating_mul(mem::size_of::<T>()));
+            if state.alloc > state.limits.max_alloc {
+                Err(exceeded(Limit::Alloc, state.limits.max_alloc))
+            } else {
+                cell.set(Some(state));
+                Ok(())
+            }
+        }
+        None => Ok(()),
+    })
+}
+
+pub(crate) fn capacity<T>(len: usize) -> usize {
+    len.min(MAX_PREALLOC / mem::size_of::<T>().max(1))
+}
+
+pub(crate) fn read_vec<R: Read>(mut reader: R, len: usize) -> Result<Vec<u8>, Error> {
+    let mut data = Vec::with_capacity(capacity::<u8>(len));
+    while data.len() < len {
+        let start = data.len();
+        let end = len.min(start + MAX_PREALLOC);
+        data.resize(end, 0);
+
+        error::read_exact(&mut reader, &mut data[start..]).map_err(|err| match err.kind() {
+            ErrorKind::UnexpectedEof { needed } => Error::unexpected_eof(needed + (len - end)),
+            _ => err,
+        })?;
+    }
+
+    Ok(data)
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                       struct Limited                                       │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+struct Limited<R> {
+    inner: R,
+    left: usize,
+    max: usize,
+}
+
+impl<R: Read> Read for Limited<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        } else if self.left == 0 {
+            return Err(exceeded(Limit::Bytes, self.max).into());
+        }
+
+        let max = buf.len().min(self.left);
+        let read = self.inner.read(&mut buf[..max])?;
+        self.left -= read;
+
+        Ok(read)
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(all(test, feature = "collections", feature = "std"))]
+#[test]
+fn limits() {
+    use crate::prefix::{Prefixed, U64};
+    use crate::Encode;
+
+    let limits = DecodeLimits { max_bytes: 8, ..Default::default() };
+    assert_eq!(limits.decode::<u64>(&[1, 0, 0, 0, 0, 0, 0, 0]).unwrap(), 1);
+    let err = limits.decode::<(u64, u8)>(&[0; 9]).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::LimitExceeded { limit: Limit::Bytes, max: 8 }));
+    assert_eq!(err.offset(), 8);
+
+    let encoded = vec![0u32; 100].encode().unwrap();
+    let limits = DecodeLimits { max_len: 99, ..Default::default() };
+    assert!(matches!(limits.decode::<Vec<u32>>(&encoded).unwrap_err().kind(), ErrorKind::LimitExceeded { .. }));
+    let limits = DecodeLimits { max_alloc: 399, ..Default::default() };
+    assert!(matches!(limits.decode::<Vec<u32>>(&encoded).unwrap_err().kind(), ErrorKind::LimitExceeded { .. }));
+    let limits = DecodeLimits { max_alloc: 400, ..Default::default() };
+    assert_eq!(limits.decode::<Vec<u32>>(&encoded).unwrap(), vec![0; 100]);
+
+    let nested = Some(Some(Some(Box::new(1u8))));
+    let encoded = nested.encode().unwrap();
+    let limits = DecodeLimits { max_depth: 3, ..Default::default() };
+    assert!(matches!(limits.decode::<Option<Option<Option<Box<u8>>>>>(&encoded).unwrap_err().kind(), ErrorKind::LimitExceeded {
+        limit: Limit::Depth,
+        ..
+    }));
+    let limits = DecodeLimits { max_depth: 4, ..Default::default() };
+    assert_eq!(limits.decode::<Option<Option<Option<Box<u8>>>>>(&encoded).unwrap(), nested);
+
+    let bogus = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f];
+    let err = Prefixed::<Vec<u8>, U64>::decode(&bogus).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::UnexpectedEof { .. }));
+}
+
+#[cfg(all(test, not(feature = "std")))]
+#[test]
+fn unsupported() {
+    let limits = DecodeLimits { max_bytes: 1, ..Default::default() };
+    assert!(matches!(limits.decode::<u16>(&[0, 0]).unwrap_err().kind(), ErrorKind::LimitExceeded { .. }));
+
+    let limits = DecodeLimits { max_depth: 8, ..Default::default() };
+    assert!(matches!(limits.decode::<u8>(&[0]).unwrap_err().kind(), ErrorKind::Unsupported { .. }));
+}
@@ -0,0 +1,312 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use crate::endian::BigEndian;
+use crate::io::{Read, Write};
+use crate::limits::{self, Depth};
+use crate::varint;
+use crate::{Decode, Encode, Error, Trace};
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{Deref, DerefMut};
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        trait Prefix                                        │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+pub trait Prefix {
+    const MAX: u64;
+
+    fn fast_size(len: usize) -> usize;
+
+    fn encode_into<W: Write>(len: usize, writer: W) -> Result<(), Error>;
+
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(usize, usize), Error>;
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                 trait {Decode,}Collection                                  │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+pub trait Collection {
+    type Error: From<Error> + Trace;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn elems_size(&self) -> Result<usize, Self::Error>;
+
+    fn elems_fast_size(&self) -> usize;
+
+    fn encode_elems_into<W: Write>(&self, writer: W) -> Result<(), Self::Error>;
+}
+
+pub trait DecodeCollection: Collection + Sized {
+    fn decode_elems_with_read_from<R: Read>(len: usize, reader: R) -> Result<(Self, usize), Self::Error>;
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                   struct Prefixed<T, P>                                    │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Prefixed<T, P = U16> {
+    inner: T,
+    _prefix: PhantomData<P>,
+}
+
+impl<T, P> Prefixed<T, P> {
+    pub fn new(inner: T) -> Self {
+        Prefixed { inner, _prefix: PhantomData }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T, P> From<T> for Prefixed<T, P> {
+    fn from(inner: T) -> Self {
+        Prefixed::new(inner)
+    }
+}
+
+impl<T, P> Deref for Prefixed<T, P> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T, P> DerefMut for Prefixed<T, P> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                            impl {En,De}code for Prefixed<T, P>                             │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl<T: Collection, P: Prefix> Encode for Prefixed<T, P> {
+    type Error = T::Error;
+
+    fn size(&self) -> Result<usize, Self::Error> {
+        size::<P, _>(&self.inner)
+    }
+
+    fn fast_size(&self) -> usize {
+        fast_size::<P, _>(&self.inner)
+    }
+
+    fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+        encode_into::<P, _, _>(&self.inner, writer)
+    }
+}
+
+impl<T: DecodeCollection, P: Prefix> Decode for Prefixed<T, P> {
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+        let (inner, read) = decode_with_read_from::<P, T, _>(reader)?;
+        Ok((Prefixed::new(inner), read))
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                       fn {en,de}code                                       │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+pub(crate) fn size<P: Prefix, C: Collection + ?Sized>(coll: &C) -> Result<usize, C::Error> {
+    if coll.len() as u64 > P::MAX {
+        Err(Error::length_overflow(P::MAX, coll.len() as u64).into())
+    } else {
+        Ok(P::fast_size(coll.len()) + coll.elems_size()?)
+    }
+}
+
+pub(crate) fn fast_size<P: Prefix, C: Collection + ?Sized>(coll: &C) -> usize {
+    if coll.len() as u64 > P::MAX {
+        0
+    } else {
+        P::fast_size(coll.len()) + coll.elems_fast_size()
+    }
+}
+
+pub(crate) fn encode_into<P, C, W>(coll: &C, mut writer: W) -> Result<(), C::Error>
+where
+    P: Prefix,
+    C: Collection + ?Sized,
+    W: Write,
+{
+    P::encode_into(coll.len(), &mut writer)?;
+    coll.encode_elems_into(writer)
+}
+
+pub(crate) fn decode_with_read_from<P, C, R>(mut reader: R) -> Result<(C, usize), C::Error>
+where
+    P: Prefix,
+    C: DecodeCollection,
+    R: Read,
+{
+    let _depth = Depth::enter()?;
+    let (len, read1) = P::decode_with_read_from(&mut reader)?;
+    limits::len(len)?;
+
+    let (coll, read2) = C::decode_elems_with_read_from(len, reader).map_err(|err| err.trace(None, read1))?;
+
+    Ok((coll, read1 + read2))
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                           Macros                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+macro_rules! prefix {
+    ($name:ident, $int:ty) => {
+        #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+        pub struct $name;
+
+        impl Prefix for $name {
+            const MAX: u64 = <$int>::MAX as u64;
+
+            fn fast_size(_: usize) -> usize {
+                mem::size_of::<$int>()
+            }
+
+            fn encode_into<W: Write>(len: usize, writer: W) -> Result<(), Error> {
+                if len as u64 > Self::MAX {
+                    Err(Error::length_overflow(Self::MAX, len as u64))
+                } else {
+                    (len as $int).encode_into(writer)
+                }
+            }
+
+            fn decode_with_read_from<R: Read>(reader: R) -> Result<(usize, usize), Error> {
+                let (len, read) = <$int>::decode_with_read_from(reader)?;
+                if len as u64 > usize::MAX as u64 {
+                    Err(Error::length_overflow(usize::MAX as u64, len as u64))
+                } else {
+                    Ok((len as usize, read))
+                }
+            }
+        }
+
+        impl Prefix for BigEndian<$name> {
+            const MAX: u64 = <$int>::MAX as u64;
+
+            fn fast_size(_: usize) -> usize {
+                mem::size_of::<$int>()
+            }
+
+            fn encode_into<W: Write>(len: usize, writer: W) -> Result<(), Error> {
+                if len as u64 > Self::MAX {
+                    Err(Error::length_overflow(Self::MAX, len as u64))
+                } else {
+                    BigEndian(len as $int).encode_into(writer)
+                }
+            }
+
+            fn decode_with_read_from<R: Read>(reader: R) -> Result<(usize, usize), Error> {
+                let (BigEndian(len), read) = BigEndian::<$int>::decode_with_read_from(reader)?;
+                if len as u64 > usize::MAX as u64 {
+                    Err(Error::length_overflow(usize::MAX as u64, len as u64))
+                } else {
+                    Ok((len as usize, read))
+                }
+            }
+        }
+    };
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        prefix!(..)                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+prefix!(U8, u8);
+prefix!(U16, u16);
+prefix!(U32, u32);
+prefix!(U64, u64);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                       struct Varint                                        │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Varint;
+
+impl Prefix for Varint {
+    const MAX: u64 = u64::MAX;
+
+    fn fast_size(len: usize) -> usize {
+        varint::fast_size(len as u128)
+    }
+
+    fn encode_into<W: Write>(len: usize, writer: W) -> Result<(), Error> {
+        varint::encode_into(len as u128, writer)
+    }
+
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(usize, usize), Error> {
+        let (len, read) = varint::decode_with_read_from(64, reader)?;
+        if len > usize::MAX as u128 {
+            Err(Error::length_overflow(usize::MAX as u64, len as u64))
+        } else {
+            Ok((len as usize, read))
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(all(test, feature = "collections"))]
+#[test]
+fn prefixed() {
+    let vec = vec![1u16, 2, 3];
+
+    let prefixed = Prefixed::<_, U8>::new(vec.clone());
+    let encoded = prefixed.encode().unwrap();
+    assert_eq!(encoded, [3, 1, 0, 2, 0, 3, 0]);
+    assert_eq!(prefixed.fast_size(), encoded.len());
+    assert_eq!(Prefixed::<Vec<u16>, U8>::decode(&encoded).unwrap(), prefixed);
+
+    let prefixed = Prefixed::<_, U32>::new(vec.clone());
+    assert_eq!(prefixed.encode().unwrap()[..4], [3, 0, 0, 0]);
+
+    let prefixed = Prefixed::<_, U16>::new(vec.clone());
+    assert_eq!(prefixed.encode().unwrap(), vec.encode().unwrap());
+
+    let prefixed = Prefixed::<_, BigEndian<U32>>::new(vec.clone());
+    assert_eq!(prefixed.encode().unwrap()[..4], [0, 0, 0, 3]);
+    assert_eq!(Prefixed::<Vec<u16>, BigEndian<U32>>::decode(&prefixed.encode().unwrap()).unwrap(), prefixed);
+
+    let prefixed = Prefixed::<_, U8>::new(vec![0u8; 256]);
+    assert!(prefixed.encode().is_err());
+
+    let prefixed = Prefixed::<_, U32>::new(vec![0u8; 70000]);
+    let encoded = prefixed.encode().unwrap();
+    assert_eq!(encoded.len(), 70004);
+    assert_eq!(Prefixed::<Vec<u8>, U32>::decode(&encoded).unwrap(), prefixed);
+}
+
+#[cfg(all(test, feature = "collections"))]
+#[test]
+fn varint() {
+    let prefixed = Prefixed::<_, Varint>::new(vec![0u8; 300]);
+    let encoded = prefixed.encode().unwrap();
+    assert_eq!(encoded[..2], [0xac, 0x02]);
+    assert_eq!(prefixed.fast_size(), encoded.len());
+    assert_eq!(Prefixed::<Vec<u8>, Varint>::decode(&encoded).unwrap(), prefixed);
+}
@@ -0,0 +1,593 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use crate::endian::BigEndian;
+use crate::prefix::{self, Prefixed, U16, U32, U64, U8};
+use crate::varint::{Varint, ZigZag};
+use alloc::borrow::{Cow, ToOwned};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+use core::mem;
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        trait Schema                                        │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Describes the bytes produced by a type's `Encode` impl, so that two versions of a protocol can
+// be compared with `check()` before they're deployed.
+pub trait Schema {
+    fn schema() -> Layout;
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        enum Layout                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Layout {
+    Bool,
+    Char,
+    Int { size: usize, signed: bool, endian: Endian },
+    Float { size: usize, endian: Endian },
+    Varint { bits: usize, signed: bool },
+    Array { elem: Box<Layout>, len: usize },
+    Str { prefix: Box<Layout> },
+    Seq { prefix: Box<Layout>, elem: Box<Layout> },
+    Map { prefix: Box<Layout>, key: Box<Layout>, value: Box<Layout> },
+    Option(Box<Layout>),
+    Tuple(Vec<Layout>),
+    Struct { name: &'static str, fields: Vec<Field> },
+    // `prefix` is the length prefix of each variant's payload, for enums with an unknown-variant
+    // fallback (see `tagged`).
+    Enum { name: &'static str, tag: Box<Layout>, prefix: Option<Box<Layout>>, variants: Vec<Variant> },
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Field {
+    pub name: &'static str,
+    pub layout: Layout,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Variant {
+    pub name: &'static str,
+    pub tag: u64,
+    pub fields: Vec<Field>,
+}
+
+impl Layout {
+    // Replaces the length prefix of a string, sequence or map (used by `Prefixed<T, P>`).
+    fn with_prefix(self, layout: Layout) -> Self {
+        let prefix = Box::new(layout);
+        match self {
+            Layout::Str { .. } => Layout::Str { prefix },
+            Layout::Seq { elem, .. } => Layout::Seq { prefix, elem },
+            Layout::Map { key, value, .. } => Layout::Map { prefix, key, value },
+            layout => layout,
+        }
+    }
+
+    // Structs and tuples encode their fields one after the other, so they're interchangeable on
+    // the wire.
+    fn fields(&self) -> Option<Vec<(Step, &Layout)>> {
+        match self {
+            Layout::Tuple(elems) => {
+                Some(elems.iter().enumerate().map(|(idx, elem)| (Step::Index(idx), elem)).collect())
+            }
+            Layout::Struct { fields, .. } => Some(named(fields)),
+            _ => None,
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                   struct Incompatibility                                   │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Incompatibility {
+    // Outermost step first.
+    pub path: Vec<Step>,
+    pub change: Change,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Step {
+    Field(&'static str),
+    Index(usize),
+    Variant(&'static str),
+    Prefix,
+    Tag,
+    Elem,
+    Key,
+    Value,
+    Some,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Change {
+    Layout { old: Layout, new: Layout },
+    Len { old: usize, new: usize },
+    Fields { old: usize, new: usize },
+    // Old peers can't decode values using this variant.
+    VariantAdded { name: &'static str, tag: u64 },
+    // New peers can't decode values using this variant.
+    VariantRemoved { name: &'static str, tag: u64 },
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                         fn check()                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Lists every wire-incompatible change between `old` and `new`. Renaming types, fields or variants
+// is fine since names are never encoded.
+pub fn check(old: &Layout, new: &Layout) -> Vec<Incompatibility> {
+    let mut found = Vec::new();
+    diff(old, new, &mut Vec::new(), &mut found);
+
+    found
+}
+
+fn diff(old: &Layout, new: &Layout, path: &mut Vec<Step>, found: &mut Vec<Incompatibility>) {
+    match (old, new) {
+        (Layout::Array { elem: old_elem, len: old_len }, Layout::Array { elem: new_elem, len: new_len }) => {
+            if old_len != new_len {
+                found
+                    .push(Incompatibility { path: path.clone(), change: Change::Len { old: *old_len, new: *new_len } });
+            }
+
+            nested(Step::Elem, old_elem, new_elem, path, found);
+        }
+        (Layout::Str { prefix: old }, Layout::Str { prefix: new }) => nested(Step::Prefix, old, new, path, found),
+        (Layout::Seq { prefix: old_prefix, elem: old_elem }, Layout::Seq { prefix: new_prefix, elem: new_elem }) => {
+            nested(Step::Prefix, old_prefix, new_prefix, path, found);
+            nested(Step::Elem, old_elem, new_elem, path, found);
+        }
+        (
+            Layout::Map { prefix: old_prefix, key: old_key, value: old_value },
+            Layout::Map { prefix: new_prefix, key: new_key, value: new_value },
+        ) => {
+            nested(Step::Prefix, old_prefix, new_prefix, path, found);
+            nested(Step::Key, old_key, new_key, path, found);
+            nested(Step::Value, old_value, new_value, path, found);
+        }
+        (Layout::Option(old), Layout::Option(new)) => nested(Step::Some, old, new, path, found),
+        (
+            Layout::Enum { tag: old_tag, prefix: old_prefix, variants: old_variants, .. },
+            Layout::Enum { tag: new_tag, prefix: new_prefix, variants: new_variants, .. },
+        ) => {
+            nested(Step::Tag, old_tag, new_tag, path, found);
+            match (old_prefix, new_prefix) {
+                (Some(old_prefix), Some(new_prefix)) => nested(Step::Prefix, old_prefix, new_prefix, path, found),
+                (None, None) => (),
+                _ => {
+                    found.push(Incompatibility {
+                        path: path.clone(),
+                        change: Change::Layout { old: old.clone(), new: new.clone() },
+                    });
+
+                    return;
+                }
+            }
+
+            for old in old_variants {
+                match new_variants.iter().find(|new| new.tag == old.tag) {
+                    Some(new) => {
+                        path.push(Step::Variant(new.name));
+                        fields(named(&old.fields), named(&new.fields), path, found);
+                        path.pop();
+                    }
+                    None if new_prefix.is_none() => found.push(Incompatibility {
+                        path: path.clone(),
+                        change: Change::VariantRemoved { name: old.name, tag: old.tag },
+                    }),
+                    None => (),
+                }
+            }
+
+            // Variants that a peer doesn't know about are kept as `Unknown`s when the payloads are
+            // prefixed.
+            for new in new_variants {
+                if new_prefix.is_none() && !old_variants.iter().any(|old| old.tag == new.tag) {
+                    found.push(Incompatibility {
+                        path: path.clone(),
+                        change: Change::VariantAdded { name: new.name, tag: new.tag },
+                    });
+                }
+            }
+        }
+        (old, new) => match (old.fields(), new.fields()) {
+            (Some(old), Some(new)) => fields(old, new, path, found),
+            _ if old != new => found.push(Incompatibility {
+                path: path.clone(),
+                change: Change::Layout { old: old.clone(), new: new.clone() },
+            }),
+            _ => (),
+        },
+    }
+}
+
+fn nested(step: Step, old: &Layout, new: &Layout, path: &mut Vec<Step>, found: &mut Vec<Incompatibility>) {
+    path.push(step);
+    diff(old, new, path, found);
+    path.pop();
+}
+
+fn fields(
+    old: Vec<(Step, &Layout)>,
+    new: Vec<(Step, &Layout)>,
+    path: &mut Vec<Step>,
+    found: &mut Vec<Incompatibility>,
+) {
+    if old.len() != new.len() {
+        found.push(Incompatibility { path: path.clone(), change: Change::Fields { old: old.len(), new: new.len() } });
+    }
+
+    for ((_, old), (step, new)) in old.into_iter().zip(new) {
+        nested(step, old, new, path, found);
+    }
+}
+
+fn named(fields: &[Field]) -> Vec<(Step, &Layout)> {
+    fields.iter().map(|field| (Step::Field(field.name), &field.layout)).collect()
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        impl Display                                        │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Display for Incompatibility {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.path.is_empty() {
+            f.write_str("<root>")?;
+        }
+
+        for step in &self.path {
+            write!(f, "{}", step)?;
+        }
+
+        write!(f, ": {}", self.change)
+    }
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Step::Field(name) => write!(f, ".{}", name),
+            Step::Index(idx) => write!(f, ".{}", idx),
+            Step::Variant(name) => write!(f, "::{}", name),
+            Step::Prefix => f.write_str(".<prefix>"),
+            Step::Tag => f.write_str(".<tag>"),
+            Step::Elem => f.write_str("[_]"),
+            Step::Key => f.write_str(".<key>"),
+            Step::Value => f.write_str(".<value>"),
+            Step::Some => f.write_str(".<some>"),
+        }
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Change::Layout { old, new } => write!(f, "layout changed from {:?} to {:?}", old, new),
+            Change::Len { old, new } => write!(f, "length changed from {} to {}", old, new),
+            Change::Fields { old, new } => write!(f, "field count changed from {} to {}", old, new),
+            Change::VariantAdded { name, tag } => write!(f, "variant {} (tag {}) added", name, tag),
+            Change::VariantRemoved { name, tag } => write!(f, "variant {} (tag {}) removed", name, tag),
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                           Macros                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+macro_rules! int {
+    ($($int:ty: $signed:expr),+) => {
+        $(
+            impl Schema for $int {
+                fn schema() -> Layout {
+                    Layout::Int { size: mem::size_of::<$int>(), signed: $signed, endian: Endian::Little }
+                }
+            }
+
+            impl Schema for BigEndian<$int> {
+                fn schema() -> Layout {
+                    Layout::Int { size: mem::size_of::<$int>(), signed: $signed, endian: Endian::Big }
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! float {
+    ($($float:ty),+) => {
+        $(
+            impl Schema for $float {
+                fn schema() -> Layout {
+                    Layout::Float { size: mem::size_of::<$float>(), endian: Endian::Little }
+                }
+            }
+
+            impl Schema for BigEndian<$float> {
+                fn schema() -> Layout {
+                    Layout::Float { size: mem::size_of::<$float>(), endian: Endian::Big }
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! varint {
+    ($($unsigned:ty, $signed:ty);+) => {
+        $(
+            impl Schema for Varint<$unsigned> {
+                fn schema() -> Layout {
+                    Layout::Varint { bits: mem::size_of::<$unsigned>() * 8, signed: false }
+                }
+            }
+
+            impl Schema for ZigZag<$signed> {
+                fn schema() -> Layout {
+                    Layout::Varint { bits: mem::size_of::<$signed>() * 8, signed: true }
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! prefix {
+    ($($name:ident: $int:ty),+) => {
+        $(
+            impl Schema for $name {
+                fn schema() -> Layout {
+                    <$int>::schema()
+                }
+            }
+
+            impl Schema for BigEndian<$name> {
+                fn schema() -> Layout {
+                    BigEndian::<$int>::schema()
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! tuple {
+    ($($name:ident),+) => {
+        impl<$($name: Schema),+> Schema for ($($name),+) {
+            fn schema() -> Layout {
+                Layout::Tuple(Vec::from([$($name::schema()),+]))
+            }
+        }
+    };
+}
+
+macro_rules! tuples {
+    ($name1:ident, $name2:ident $(, $($name:ident),+)?) => {
+        tuples!(@INTERNAL; ($name1, $name2) ($($($name),+)?));
+    };
+
+    (@INTERNAL; ($($name:ident),+) ()) => {
+        tuple!($($name),+);
+    };
+
+    (@INTERNAL; ($($name:ident),+) ($oname:ident $(, $($rname:ident),+)?)) => {
+        tuple!($($name),+);
+        tuples!(@INTERNAL; ($($name),+, $oname) ($($($rname),+)?));
+    };
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          int!(..)                                          │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+int!(u8: false, u16: false, u32: false, u64: false, u128: false);
+int!(i8: true, i16: true, i32: true, i64: true, i128: true);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                         float!(..)                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+float!(f32, f64);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        varint!(..)                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+varint!(u8, i8; u16, i16; u32, i32; u64, i64; u128, i128; usize, isize);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        prefix!(..)                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+prefix!(U8: u8, U16: u16, U32: u32, U64: u64);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        tuples!(..)                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+tuples!(A, B, C, D, E, F, G, H, I, J, K, L, M);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                               impl Schema for {usize,isize}                                │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Schema for usize {
+    fn schema() -> Layout {
+        u64::schema()
+    }
+}
+
+impl Schema for isize {
+    fn schema() -> Layout {
+        i64::schema()
+    }
+}
+
+impl Schema for BigEndian<usize> {
+    fn schema() -> Layout {
+        BigEndian::<u64>::schema()
+    }
+}
+
+impl Schema for BigEndian<isize> {
+    fn schema() -> Layout {
+        BigEndian::<i64>::schema()
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                impl Schema for {bool,char}                                 │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl Schema for bool {
+    fn schema() -> Layout {
+        Layout::Bool
+    }
+}
+
+impl Schema for char {
+    fn schema() -> Layout {
+        Layout::Char
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                         impl Schema for [T; _], [u8], str, String                          │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl<T: Schema, const LEN: usize> Schema for [T; LEN] {
+    fn schema() -> Layout {
+        Layout::Array { elem: Box::new(T::schema()), len: LEN }
+    }
+}
+
+impl Schema for [u8] {
+    fn schema() -> Layout {
+        Layout::Seq { prefix: Box::new(U16::schema()), elem: Box::new(u8::schema()) }
+    }
+}
+
+impl Schema for str {
+    fn schema() -> Layout {
+        Layout::Str { prefix: Box::new(U16::schema()) }
+    }
+}
+
+impl Schema for String {
+    fn schema() -> Layout {
+        str::schema()
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                impl Schema for &T, Box<T>, Cow<B>, Option<T>, Result<T, E>                 │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl<T: Schema + ?Sized> Schema for &T {
+    fn schema() -> Layout {
+        T::schema()
+    }
+}
+
+impl<T: Schema + ?Sized> Schema for Box<T> {
+    fn schema() -> Layout {
+        T::schema()
+    }
+}
+
+impl<B: Schema + ToOwned + ?Sized> Schema for Cow<'_, B> {
+    fn schema() -> Layout {
+        B::schema()
+    }
+}
+
+impl<T: Schema> Schema for Option<T> {
+    fn schema() -> Layout {
+        Layout::Option(Box::new(T::schema()))
+    }
+}
+
+impl<T: Schema, E: Schema> Schema for Result<T, E> {
+    fn schema() -> Layout {
+        let variant = |name, tag, layout| Variant { name, tag, fields: Vec::from([Field { name: "0", layout }]) };
+        Layout::Enum {
+            name: "Result",
+            tag: Box::new(u8::schema()),
+            prefix: None,
+            variants: Vec::from([variant("Ok", 0, T::schema()), variant("Err", 1, E::schema())]),
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                           impl Schema for Prefixed<T, P>, Varint                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl<T: Schema, P: Schema> Schema for Prefixed<T, P> {
+    fn schema() -> Layout {
+        T::schema().with_prefix(P::schema())
+    }
+}
+
+impl Schema for prefix::Varint {
+    fn schema() -> Layout {
+        Layout::Varint { bits: 64, signed: false }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(test)]
+#[test]
+fn compatibility() {
+    let old = Layout::Struct {
+        name: "Old",
+        fields: Vec::from([
+            Field { name: "id", layout: u32::schema() },
+            Field { name: "name", layout: <Option<String>>::schema() },
+            Field { name: "pair", layout: <(u8, [u16; 2])>::schema() },
+        ]),
+    };
+
+    let renamed = Layout::Tuple(Vec::from([u32::schema(), <Option<&str>>::schema(), <(u8, [u16; 2])>::schema()]));
+    assert_eq!(check(&old, &renamed), []);
+
+    let new = Layout::Struct {
+        name: "New",
+        fields: Vec::from([
+            Field { name: "id", layout: Varint::<u32>::schema() },
+            Field { name: "name", layout: <Option<Prefixed<String, U8>>>::schema() },
+            Field { name: "pair", layout: <(u8, [u16; 3])>::schema() },
+            Field { name: "extra", layout: bool::schema() },
+        ]),
+    };
+
+    let found = check(&old, &new).iter().map(|found| found.to_string()).collect::<Vec<_>>();
+    assert_eq!(found[0], "<root>: field count changed from 3 to 4");
+    assert!(found[1].starts_with(".id: layout changed from Int"));
+    assert!(found[2].starts_with(".name.<some>.<prefix>: layout changed"));
+    assert_eq!(found[3], ".pair.1: length changed from 2 to 3");
+    assert_eq!(found.len(), 4);
+}
@@ -0,0 +1,225 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use crate::io::{Read, Write};
+use crate::limits::{self, Depth};
+use crate::prefix::{Prefix, U32};
+use crate::schema::{Field, Layout, Schema};
+use crate::{Decode, Encode, Error, Trace};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use chacha20poly1305::aead::{Aead, NewAead};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use core::fmt::{self, Debug, Formatter};
+use core::marker::PhantomData;
+use rand_core::{CryptoRng, RngCore};
+use sha2::digest::FixedOutput;
+use sha2::{Digest, Sha256};
+use x25519::{EphemeralSecret, PublicKey, SharedSecret, StaticSecret};
+use zeroize::Zeroizing;
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                      struct Sealed<T>                                      │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// The encoding of a value, encrypted for the owner of `recipient` using a key derived from an
+// ephemeral x25519 exchange, encoded as the ephemeral public key, the nonce and the `U32`-prefixed
+// ChaCha20-Poly1305 ciphertext. Only the recipient's `StaticSecret` can `open` it.
+pub struct Sealed<T> {
+    ephemeral: PublicKey,
+    nonce: [u8; 12],
+    ciphertext: Vec<u8>,
+    _val: PhantomData<fn() -> T>,
+}
+
+impl<T: Encode> Sealed<T>
+where
+    T::Error: From<Error>,
+{
+    pub fn seal<R: CryptoRng + RngCore>(csprng: &mut R, recipient: &PublicKey, val: &T) -> Result<Self, T::Error> {
+        let secret = EphemeralSecret::new(&mut *csprng);
+        let ephemeral = PublicKey::from(&secret);
+        let cipher = cipher(secret.diffie_hellman(recipient), &ephemeral, recipient)?;
+
+        let mut nonce = [0; 12];
+        csprng.fill_bytes(&mut nonce);
+
+        let plaintext = Zeroizing::new(val.encode()?);
+        let ciphertext = cipher
+            .encrypt(&Nonce::from(nonce), &plaintext[..])
+            .map_err(|_| Error::invalid_data("encryption failed"))?;
+
+        Ok(Sealed { ephemeral, nonce, ciphertext, _val: PhantomData })
+    }
+}
+
+impl<T: Decode> Sealed<T>
+where
+    T::Error: From<Error>,
+{
+    pub fn open(&self, secret: &StaticSecret) -> Result<T, T::Error> {
+        let recipient = PublicKey::from(secret);
+        let cipher = cipher(secret.diffie_hellman(&self.ephemeral), &self.ephemeral, &recipient)?;
+        let plaintext = cipher
+            .decrypt(&Nonce::from(self.nonce), &self.ciphertext[..])
+            .map(Zeroizing::new)
+            .map_err(|_| Error::invalid_data("invalid ciphertext"))?;
+
+        let (val, read) = T::decode_with_read(&plaintext)?;
+        if read != plaintext.len() {
+            return Err(Error::invalid_data("trailing bytes in sealed payload").into());
+        }
+
+        Ok(val)
+    }
+}
+
+impl<T> Sealed<T> {
+    pub fn ephemeral(&self) -> &PublicKey {
+        &self.ephemeral
+    }
+}
+
+// Implemented by hand since deriving them would require `T` to implement them too.
+impl<T> Clone for Sealed<T> {
+    fn clone(&self) -> Self {
+        Sealed { ephemeral: self.ephemeral, nonce: self.nonce, ciphertext: self.ciphertext.clone(), _val: PhantomData }
+    }
+}
+
+impl<T> Debug for Sealed<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Sealed")
+            .field("ephemeral", &self.ephemeral)
+            .field("nonce", &self.nonce)
+            .field("ciphertext", &self.ciphertext)
+            .finish()
+    }
+}
+
+impl<T> PartialEq for Sealed<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ephemeral == other.ephemeral && self.nonce == other.nonce && self.ciphertext == other.ciphertext
+    }
+}
+
+impl<T> Eq for Sealed<T> {}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        fn cipher()                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+fn cipher(shared: SharedSecret, ephemeral: &PublicKey, recipient: &PublicKey) -> Result<ChaCha20Poly1305, Error> {
+    // A low order public key would make the shared secret (and thus the key) predictable.
+    if shared.as_bytes() == &[0; 32] {
+        return Err(Error::invalid_data("invalid public key"));
+    }
+
+    let mut key = Zeroizing::new([0; 32]);
+    Sha256::new()
+        .chain(b"f0rm47 sealed")
+        .chain(shared.as_bytes())
+        .chain(ephemeral.as_bytes())
+        .chain(recipient.as_bytes())
+        .finalize_into((&mut key[..]).into());
+
+    Ok(ChaCha20Poly1305::new((&key[..]).into()))
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                               impl {En,De}code for Sealed<T>                               │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl<T> Encode for Sealed<T> {
+    type Error = Error;
+
+    fn fast_size(&self) -> usize {
+        self.ephemeral.fast_size()
+            + self.nonce.fast_size()
+            + U32::fast_size(self.ciphertext.len())
+            + self.ciphertext.len()
+    }
+
+    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        self.ephemeral.encode_into(&mut writer)?;
+        self.nonce.encode_into(&mut writer)?;
+        U32::encode_into(self.ciphertext.len(), &mut writer)?;
+        Ok(writer.write_all(&self.ciphertext)?)
+    }
+}
+
+impl<T> Decode for Sealed<T> {
+    fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
+        let _depth = Depth::enter()?;
+        let (ephemeral, read1) = PublicKey::decode_with_read_from(&mut reader)?;
+        let (nonce, read2) = <[u8; 12]>::decode_with_read_from(&mut reader).map_err(|err| err.trace(None, read1))?;
+        let (len, read3) = U32::decode_with_read_from(&mut reader).map_err(|err| err.trace(None, read1 + read2))?;
+        limits::len(len)?;
+        limits::alloc::<u8>(len)?;
+
+        let ciphertext = limits::read_vec(reader, len).map_err(|err| err.trace(None, read1 + read2 + read3))?;
+        Ok((Sealed { ephemeral, nonce, ciphertext, _val: PhantomData }, read1 + read2 + read3 + len))
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                 impl Schema for Sealed<T>                                  │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl<T> Schema for Sealed<T> {
+    fn schema() -> Layout {
+        Layout::Struct {
+            name: "Sealed",
+            fields: Vec::from([
+                Field { name: "ephemeral", layout: PublicKey::schema() },
+                Field { name: "nonce", layout: <[u8; 12]>::schema() },
+                Field {
+                    name: "ciphertext",
+                    layout: Layout::Seq { prefix: Box::new(U32::schema()), elem: Box::new(u8::schema()) },
+                },
+            ]),
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(test)]
+#[test]
+fn sealed() {
+    use crate::ErrorKind;
+    use rand::rngs::OsRng;
+
+    let secret = StaticSecret::new(OsRng);
+    let recipient = PublicKey::from(&secret);
+    let sealed = Sealed::seal(&mut OsRng, &recipient, &(1u8, 2u32)).unwrap();
+    let encoded = sealed.encode().unwrap();
+    assert_eq!(encoded.len(), 32 + 12 + 4 + 5 + 16);
+
+    let decoded = Sealed::<(u8, u32)>::decode(&encoded).unwrap();
+    assert_eq!(decoded, sealed);
+    assert_eq!(decoded.open(&secret).unwrap(), (1, 2));
+
+    let other = StaticSecret::new(OsRng);
+    let err = decoded.open(&other).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidData { context: "invalid ciphertext" }));
+
+    let mut tampered = encoded;
+    tampered[32 + 12 + 4] ^= 1;
+    let err = Sealed::<(u8, u32)>::decode(&tampered).unwrap().open(&secret).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidData { context: "invalid ciphertext" }));
+
+    let err = Sealed::seal(&mut OsRng, &PublicKey::from([0; 32]), &0u8).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidData { context: "invalid public key" }));
+}
@@ -0,0 +1,78 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use crate::io::Read;
+use crate::{error, Encode, Error};
+use alloc::vec;
+use alloc::vec::Vec;
+use zeroize::Zeroizing;
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        fn encode()                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// `Encode::encode()` returns a plain `Vec` which isn't zeroed once dropped, so secret keys (and
+// values containing them) should be encoded with this instead, or with `Encode::encode_into()`
+// into a buffer handled by the caller. The buffer is allocated once with the exact size of the
+// value, so that no copy of it is left behind by a reallocation.
+//
+// `FrameDecoder` and `aio::StreamDecoder` zero their buffers, but the secret bytes still end up
+// in unzeroed ones when going through `Envelope`, `Signed`, `tagged::decode_payload()`,
+// `frame::Codec` and `serde`, which should thus not be used for values containing secret keys.
+pub fn encode<T: Encode + ?Sized>(val: &T) -> Result<Zeroizing<Vec<u8>>, T::Error>
+where
+    T::Error: From<Error>,
+{
+    let mut buf = Zeroizing::new(vec![0; val.size()?]);
+    let mut left = &mut buf[..];
+    val.encode_into(&mut left)?;
+    if !left.is_empty() {
+        return Err(Error::invalid_data("value smaller than its size").into());
+    }
+
+    Ok(buf)
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                         fn read()                                          │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Reads a 32 bytes secret key into a buffer that gets zeroed on drop.
+pub(crate) fn read<R: Read>(reader: R) -> Result<Zeroizing<[u8; 32]>, Error> {
+    let mut bytes = Zeroizing::new([0; 32]);
+    error::read_exact(reader, &mut bytes[..])?;
+
+    Ok(bytes)
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(all(test, feature = "ed25519"))]
+#[test]
+fn zeroized() {
+    use core::slice;
+    use ed25519::Keypair;
+    use rand::rngs::OsRng;
+    use zeroize::Zeroize;
+
+    let keypair = Keypair::generate(&mut OsRng);
+    let mut encoded = encode(&keypair).unwrap();
+    assert_eq!(&encoded[..], keypair.to_bytes());
+    assert_eq!(encoded.capacity(), encoded.len());
+
+    // This is what dropping `encoded` does before freeing it.
+    let (ptr, cap) = (encoded.as_ptr(), encoded.capacity());
+    encoded.zeroize();
+    assert!(unsafe { slice::from_raw_parts(ptr, cap) }.iter().all(|byte| *byte == 0));
+}
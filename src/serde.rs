@@ -0,0 +1,770 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use crate::io::Write;
+use crate::limits::{self, DecodeLimits, Depth, Limit};
+use crate::prefix::{Prefix, U16};
+use crate::{Decode, DecodeRef, Encode, Error, ErrorKind, Segment, Trace};
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+use serde::de::{self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess, Visitor};
+use serde::ser::{self, Serialize};
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                           fn *()                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Sequences, strings, byte strings and maps are prefixed with their length as a `u16`, options with
+// a `bool` and enum variants with their index as a `u8`, exactly like the `Encode` impls of
+// `Vec<T>`, `String`, `BTreeMap<K, V>`, `Option<T>` and `#[derive(Encode)]`'s default tag.
+
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, Error> {
+    let mut buf = Vec::new();
+    to_writer(&mut buf, value)?;
+
+    Ok(buf)
+}
+
+pub fn to_writer<T: Serialize + ?Sized, W: Write>(writer: W, value: &T) -> Result<(), Error> {
+    value.serialize(&mut Serializer::new(writer))
+}
+
+pub fn from_slice<'de, T: de::Deserialize<'de>>(buf: &'de [u8]) -> Result<T, Error> {
+    Ok(from_slice_with_read(buf)?.0)
+}
+
+pub fn from_slice_with_read<'de, T: de::Deserialize<'de>>(buf: &'de [u8]) -> Result<(T, usize), Error> {
+    let mut deserializer = Deserializer::new(buf);
+    let value = T::deserialize(&mut deserializer)?;
+
+    Ok((value, deserializer.read()))
+}
+
+// Deserializes `T` with `limits` applied, in the same way as `DecodeLimits::decode()`. The other
+// functions don't limit anything, and shouldn't be used on untrusted input.
+//
+// `max_alloc` can't be enforced since the visitors of `T` are the ones allocating, so setting it
+// is an error.
+pub fn from_slice_with_limits<'de, T: de::Deserialize<'de>>(buf: &'de [u8], limits: &DecodeLimits) -> Result<T, Error> {
+    if limits.max_alloc != usize::MAX {
+        return Err(Error::new(ErrorKind::Unsupported { context: "serde can't limit max_alloc" }));
+    }
+
+    let input = &buf[..buf.len().min(limits.max_bytes)];
+    limits.apply(|| from_slice(input)).map_err(|err| match err.kind() {
+        ErrorKind::UnexpectedEof { .. } if input.len() < buf.len() => {
+            err.with_kind(ErrorKind::LimitExceeded { limit: Limit::Bytes, max: limits.max_bytes })
+        }
+        _ => err,
+    })
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                     struct Serializer                                      │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+pub struct Serializer<W> {
+    writer: W,
+}
+
+impl<W: Write> Serializer<W> {
+    pub fn new(writer: W) -> Self {
+        Serializer { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn tag(&mut self, name: &'static str, idx: u32) -> Result<(), Error> {
+        if idx > u8::MAX as u32 {
+            Err(Error::invalid_tag(name, idx as u64))
+        } else {
+            (idx as u8).encode_into(&mut self.writer)
+        }
+    }
+
+    fn len(&mut self, len: Option<usize>) -> Result<(), Error> {
+        match len {
+            Some(len) => U16::encode_into(len, &mut self.writer),
+            None => Err(Error::invalid_data("sequence of unknown length")),
+        }
+    }
+}
+
+impl<W: Write> ser::Serializer for &mut Serializer<W> {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, val: bool) -> Result<(), Error> {
+        val.encode_into(&mut self.writer)
+    }
+
+    fn serialize_i8(self, val: i8) -> Result<(), Error> {
+        val.encode_into(&mut self.writer)
+    }
+
+    fn serialize_i16(self, val: i16) -> Result<(), Error> {
+        val.encode_into(&mut self.writer)
+    }
+
+    fn serialize_i32(self, val: i32) -> Result<(), Error> {
+        val.encode_into(&mut self.writer)
+    }
+
+    fn serialize_i64(self, val: i64) -> Result<(), Error> {
+        val.encode_into(&mut self.writer)
+    }
+
+    fn serialize_i128(self, val: i128) -> Result<(), Error> {
+        val.encode_into(&mut self.writer)
+    }
+
+    fn serialize_u8(self, val: u8) -> Result<(), Error> {
+        val.encode_into(&mut self.writer)
+    }
+
+    fn serialize_u16(self, val: u16) -> Result<(), Error> {
+        val.encode_into(&mut self.writer)
+    }
+
+    fn serialize_u32(self, val: u32) -> Result<(), Error> {
+        val.encode_into(&mut self.writer)
+    }
+
+    fn serialize_u64(self, val: u64) -> Result<(), Error> {
+        val.encode_into(&mut self.writer)
+    }
+
+    fn serialize_u128(self, val: u128) -> Result<(), Error> {
+        val.encode_into(&mut self.writer)
+    }
+
+    fn serialize_f32(self, val: f32) -> Result<(), Error> {
+        val.encode_into(&mut self.writer)
+    }
+
+    fn serialize_f64(self, val: f64) -> Result<(), Error> {
+        val.encode_into(&mut self.writer)
+    }
+
+    fn serialize_char(self, val: char) -> Result<(), Error> {
+        val.encode_into(&mut self.writer)
+    }
+
+    fn serialize_str(self, val: &str) -> Result<(), Error> {
+        val.encode_into(&mut self.writer)
+    }
+
+    fn serialize_bytes(self, val: &[u8]) -> Result<(), Error> {
+        val.encode_into(&mut self.writer)
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        false.encode_into(&mut self.writer)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, val: &T) -> Result<(), Error> {
+        true.encode_into(&mut self.writer)?;
+        val.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(self, name: &'static str, idx: u32, _: &'static str) -> Result<(), Error> {
+        self.tag(name, idx)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _: &'static str, val: &T) -> Result<(), Error> {
+        val.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        idx: u32,
+        _: &'static str,
+        val: &T,
+    ) -> Result<(), Error> {
+        self.tag(name, idx)?;
+        val.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self, Error> {
+        self.len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(self, name: &'static str, idx: u32, _: &'static str, _: usize) -> Result<Self, Error> {
+        self.tag(name, idx)?;
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self, Error> {
+        self.len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(self, name: &'static str, idx: u32, _: &'static str, _: usize) -> Result<Self, Error> {
+        self.tag(name, idx)?;
+        Ok(self)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                            impl Serialize* for &mut Serializer                             │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+macro_rules! serialize {
+    ($trait:ident, $method:ident $(, $name:ty)?) => {
+        impl<W: Write> ser::$trait for &mut Serializer<W> {
+            type Ok = ();
+            type Error = Error;
+
+            fn $method<T: Serialize + ?Sized>(&mut self, $(_: $name,)? val: &T) -> Result<(), Error> {
+                val.serialize(&mut **self)
+            }
+
+            fn end(self) -> Result<(), Error> {
+                Ok(())
+            }
+        }
+    };
+}
+
+serialize!(SerializeSeq, serialize_element);
+serialize!(SerializeTuple, serialize_element);
+serialize!(SerializeTupleStruct, serialize_field);
+serialize!(SerializeTupleVariant, serialize_field);
+serialize!(SerializeStruct, serialize_field, &'static str);
+serialize!(SerializeStructVariant, serialize_field, &'static str);
+
+impl<W: Write> ser::SerializeMap for &mut Serializer<W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, val: &T) -> Result<(), Error> {
+        val.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                    struct Deserializer                                     │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+pub struct Deserializer<'de> {
+    input: &'de [u8],
+    read: usize,
+}
+
+impl<'de> Deserializer<'de> {
+    pub fn new(input: &'de [u8]) -> Self {
+        Deserializer { input, read: 0 }
+    }
+
+    pub fn read(&self) -> usize {
+        self.read
+    }
+
+    fn decode<T: Decode<Error = Error>>(&mut self) -> Result<T, Error> {
+        let (val, read) = T::decode_with_read(&self.input[self.read..]).map_err(|err| err.trace(None, self.read))?;
+        self.read += read;
+
+        Ok(val)
+    }
+
+    fn decode_ref<T: DecodeRef<Error = Error> + ?Sized>(&mut self) -> Result<&'de T, Error> {
+        let input = self.input;
+        let (val, read) = T::decode_ref_with_read(&input[self.read..]).map_err(|err| err.trace(None, self.read))?;
+        self.read += read;
+
+        Ok(val)
+    }
+
+    fn len(&mut self) -> Result<usize, Error> {
+        let (len, read) =
+            U16::decode_with_read_from(&self.input[self.read..]).map_err(|err| err.trace(None, self.read))?;
+        self.read += read;
+        limits::len(len)?;
+
+        Ok(len)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Error> {
+        Err(Error::invalid_data("f0rm47 isn't self-describing"))
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_bool(self.decode()?)
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i8(self.decode()?)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i16(self.decode()?)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i32(self.decode()?)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i64(self.decode()?)
+    }
+
+    fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i128(self.decode()?)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u8(self.decode()?)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u16(self.decode()?)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u32(self.decode()?)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u64(self.decode()?)
+    }
+
+    fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u128(self.decode()?)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_f32(self.decode()?)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_f64(self.decode()?)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_char(self.decode()?)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_borrowed_str(self.decode_ref::<str>()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_borrowed_bytes(self.decode_ref::<[u8]>()?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.decode()? {
+            let _depth = Depth::enter()?;
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, _: &'static str, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _: &'static str, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let _depth = Depth::enter()?;
+        let len = self.len()?;
+        visitor.visit_seq(Access { de: self, idx: 0, len, fields: None })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        let _depth = Depth::enter()?;
+        visitor.visit_seq(Access { de: self, idx: 0, len, fields: None })
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let _depth = Depth::enter()?;
+        visitor.visit_seq(Access { de: self, idx: 0, len, fields: None })
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let _depth = Depth::enter()?;
+        let len = self.len()?;
+        visitor.visit_map(Access { de: self, idx: 0, len, fields: None })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let _depth = Depth::enter()?;
+        visitor.visit_seq(Access { de: self, idx: 0, len: fields.len(), fields: Some(fields) })
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let _depth = Depth::enter()?;
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Error> {
+        Err(Error::invalid_data("f0rm47 doesn't encode identifiers"))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Error> {
+        Err(Error::invalid_data("f0rm47 isn't self-describing"))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                       struct Access                                        │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+struct Access<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    idx: usize,
+    len: usize,
+    fields: Option<&'static [&'static str]>,
+}
+
+impl<'de> SeqAccess<'de> for Access<'_, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Error> {
+        if self.idx == self.len {
+            return Ok(None);
+        }
+
+        let segment = match self.fields {
+            Some(fields) => Segment::Field(fields[self.idx]),
+            None => Segment::Index(self.idx),
+        };
+
+        self.idx += 1;
+        seed.deserialize(&mut *self.de).map(Some).map_err(|err| err.trace(Some(segment), 0))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len - self.idx)
+    }
+}
+
+impl<'de> MapAccess<'de> for Access<'_, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Error> {
+        if self.idx == self.len {
+            return Ok(None);
+        }
+
+        let idx = self.idx;
+        seed.deserialize(&mut *self.de).map(Some).map_err(|err| err.trace(Some(Segment::Key(idx)), 0))
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let idx = self.idx;
+        self.idx += 1;
+        seed.deserialize(&mut *self.de).map_err(|err| err.trace(Some(Segment::Value(idx)), 0))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len - self.idx)
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                      impl {Enum,Variant}Access for &mut Deserializer                       │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl<'de> EnumAccess<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        let start = self.read;
+        let tag = self.decode::<u8>()?;
+        let val = seed.deserialize((tag as u32).into_deserializer()).map_err(|err: Error| err.trace(None, start))?;
+
+        Ok((val, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        let _depth = Depth::enter()?;
+        visitor.visit_seq(Access { de: self, idx: 0, len, fields: None })
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+        let _depth = Depth::enter()?;
+        visitor.visit_seq(Access { de: self, idx: 0, len: fields.len(), fields: Some(fields) })
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                    impl {ser,de}::Error                                    │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::new(ErrorKind::Custom(msg.to_string()))
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::new(ErrorKind::Custom(msg.to_string()))
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        mod encoded                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// For use with `#[serde(with = "f0rm47::serde::encoded")]`, to embed a field encoded with `Encode`
+// as a byte string in any other serde format.
+pub mod encoded {
+    use super::*;
+    use serde::de::Error as _;
+    use serde::ser::Error as _;
+
+    pub fn serialize<T, S>(val: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Encode,
+        T::Error: Display,
+        S: ser::Serializer,
+    {
+        serializer.serialize_bytes(&val.encode().map_err(S::Error::custom)?)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Decode,
+        T::Error: Display,
+        D: de::Deserializer<'de>,
+    {
+        let bytes = deserializer.deserialize_bytes(BytesVisitor)?;
+        let (val, read) = T::decode_with_read(&bytes).map_err(D::Error::custom)?;
+        if read != bytes.len() {
+            return Err(D::Error::custom(Error::invalid_data("trailing bytes")));
+        }
+
+        Ok(val)
+    }
+
+    struct BytesVisitor;
+
+    impl<'de> Visitor<'de> for BytesVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+            f.write_str("f0rm47-encoded bytes")
+        }
+
+        fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+            Ok(bytes.to_vec())
+        }
+
+        fn visit_byte_buf<E: de::Error>(self, bytes: Vec<u8>) -> Result<Self::Value, E> {
+            Ok(bytes)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut bytes = Vec::with_capacity(limits::capacity::<u8>(seq.size_hint().unwrap_or(0)));
+            while let Some(byte) = seq.next_element()? {
+                bytes.push(byte);
+            }
+
+            Ok(bytes)
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(all(test, feature = "collections"))]
+#[test]
+fn serde() {
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Kind<'a> {
+        Empty,
+        Named(&'a str),
+        Pair(u8, i16),
+        Fields { bytes: Vec<u8> },
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Message<'a> {
+        id: u32,
+        name: Option<String>,
+        tags: Vec<u16>,
+        map: BTreeMap<u8, bool>,
+        pair: (char, f64),
+        #[serde(borrow)]
+        kinds: Vec<Kind<'a>>,
+    }
+
+    let msg = Message {
+        id: 42,
+        name: Some("héllo".into()),
+        tags: vec![1, 2, 3],
+        map: BTreeMap::from([(1, true), (2, false)]),
+        pair: ('é', 1.5),
+        kinds: vec![Kind::Empty, Kind::Named("foo"), Kind::Pair(1, -1), Kind::Fields { bytes: vec![1, 2] }],
+    };
+
+    let kinds = [
+        vec![0],
+        (1u8, "foo").encode().unwrap(),
+        (2u8, 1u8, -1i16).encode().unwrap(),
+        (3u8, vec![1u8, 2]).encode().unwrap(),
+    ];
+    let mut expected = (msg.id, &msg.name, &msg.tags, &msg.map, msg.pair, 4u16).encode().unwrap();
+    expected.extend(kinds.concat());
+
+    let encoded = to_vec(&msg).unwrap();
+    assert_eq!(encoded, expected);
+    assert_eq!(from_slice_with_read::<Message>(&encoded).unwrap(), (msg, encoded.len()));
+
+    let err = from_slice::<Message>(&encoded[..encoded.len() - 1]).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::UnexpectedEof { needed: 1 }));
+    assert_eq!(
+        err.path().copied().collect::<Vec<_>>(),
+        [Segment::Field("kinds"), Segment::Index(3), Segment::Field("bytes"), Segment::Index(1)]
+    );
+
+    let limits = DecodeLimits { max_bytes: encoded.len() - 1, ..Default::default() };
+    let err = from_slice_with_limits::<Message>(&encoded, &limits).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::LimitExceeded { limit: Limit::Bytes, .. }));
+    let limits = DecodeLimits { max_len: 2, ..Default::default() };
+    let err = from_slice_with_limits::<Message>(&encoded, &limits).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::LimitExceeded { limit: Limit::Len, max: 2 }));
+    assert_eq!(err.path().copied().collect::<Vec<_>>(), [Segment::Field("tags")]);
+    let limits = DecodeLimits { max_alloc: 1024, ..Default::default() };
+    let err = from_slice_with_limits::<Message>(&encoded, &limits).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::Unsupported { .. }));
+
+    // Every compound counts towards `max_depth`, tuples and variants included.
+    let nested = (((1u8,),),);
+    let encoded = to_vec(&nested).unwrap();
+    let limits = DecodeLimits { max_depth: 2, ..Default::default() };
+    let err = from_slice_with_limits::<(((u8,),),)>(&encoded, &limits).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::LimitExceeded { limit: Limit::Depth, max: 2 }));
+    let limits = DecodeLimits { max_depth: 3, ..Default::default() };
+    assert_eq!(from_slice_with_limits::<(((u8,),),)>(&encoded, &limits).unwrap(), nested);
+    let encoded = to_vec(&vec![Kind::Pair(1, 2)]).unwrap();
+    let limits = DecodeLimits { max_depth: 2, ..Default::default() };
+    assert!(from_slice_with_limits::<Vec<Kind>>(&encoded, &limits).is_err());
+    let limits = DecodeLimits { max_depth: 3, ..Default::default() };
+    assert_eq!(from_slice_with_limits::<Vec<Kind>>(&encoded, &limits).unwrap(), [Kind::Pair(1, 2)]);
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Embedded {
+        #[serde(with = "encoded")]
+        addr: (u16, [u8; 4]),
+    }
+
+    let embedded = Embedded { addr: (80, [127, 0, 0, 1]) };
+    let json = serde_json::to_string(&embedded).unwrap();
+    assert_eq!(json, r#"{"addr":[80,0,127,0,0,1]}"#);
+    assert_eq!(serde_json::from_str::<Embedded>(&json).unwrap(), embedded);
+    assert!(serde_json::from_str::<Embedded>(r#"{"addr":[80,0,127,0,0,1,2]}"#).is_err());
+}
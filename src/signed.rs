@@ -0,0 +1,317 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use crate::io::{Read, Write};
+use crate::limits::{self, Depth};
+use crate::prefix::{Prefix, U16, U32};
+use crate::schema::{Field, Layout, Schema};
+use crate::{Decode, Encode, Error, Segment, Trace};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use curve25519::edwards::CompressedEdwardsY;
+use ed25519::{Keypair, PublicKey, Signature, Signer};
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                      struct Signed<T>                                      │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// A value signed by `signer`, encoded as the `U32`-prefixed encoding of the value followed by the
+// signer's public key and the signature. Decoding fails unless the signature is valid.
+//
+// The signed bytes are kept around so that the value can be passed along and re-encoded without
+// invalidating the signature (e.g. if `T` contains a `HashMap`). Signing encodes `T` as-is though,
+// so two signers only produce the same payload for the same value if `T`'s encoding is
+// deterministic: maps, sets and floats should be wrapped in `Canonical`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Signed<T> {
+    val: T,
+    payload: Vec<u8>,
+    signer: PublicKey,
+    signature: Signature,
+}
+
+impl<T: Encode> Signed<T> {
+    pub fn sign(keypair: &Keypair, val: T) -> Result<Self, T::Error> {
+        let payload = val.encode()?;
+        let signature = keypair.sign(&payload);
+
+        Ok(Signed { val, payload, signer: keypair.public, signature })
+    }
+}
+
+impl<T> Signed<T> {
+    pub fn get(&self) -> &T {
+        &self.val
+    }
+
+    pub fn into_inner(self) -> T {
+        self.val
+    }
+
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    pub fn signer(&self) -> &PublicKey {
+        &self.signer
+    }
+
+    pub fn signature(&self) -> &Signature {
+        &self.signature
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                      struct Batch<T>                                       │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Encoded like `Vec<Signed<T>>`, but decoding verifies all the signatures at once, which is about
+// twice as fast as verifying them one by one. Both reject small order public keys and `R`s, as
+// well as non-canonical `R`s, which `ed25519::verify_batch()` would otherwise accept (at random)
+// while `PublicKey::verify_strict()` doesn't.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Batch<T>(pub Vec<Signed<T>>);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                               impl {En,De}code for Signed<T>                               │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl<T: Encode> Encode for Signed<T>
+where
+    T::Error: From<Error>,
+{
+    type Error = T::Error;
+
+    fn size(&self) -> Result<usize, Self::Error> {
+        Ok(self.fast_size())
+    }
+
+    fn fast_size(&self) -> usize {
+        U32::fast_size(self.payload.len()) + self.payload.len() + self.signer.fast_size() + self.signature.fast_size()
+    }
+
+    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        U32::encode_into(self.payload.len(), &mut writer)?;
+        writer.write_all(&self.payload).map_err(Error::from)?;
+        self.signer.encode_into(&mut writer)?;
+        Ok(self.signature.encode_into(writer)?)
+    }
+}
+
+impl<T: Decode> Decode for Signed<T>
+where
+    T::Error: From<Error> + Trace,
+{
+    fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+        let (unverified, read) = Unverified::decode_with_read_from(reader)?;
+        if !unverified.verify() {
+            return Err(Error::invalid_data("invalid signature").into());
+        }
+
+        Ok((unverified.open::<T>()?, read))
+    }
+}
+
+// The parts of a `Signed<T>`, before its signature is verified and its payload decoded.
+struct Unverified {
+    payload: Vec<u8>,
+    signer: PublicKey,
+    signature: Signature,
+}
+
+impl Unverified {
+    fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Error> {
+        let _depth = Depth::enter()?;
+        let (len, read1) = U32::decode_with_read_from(&mut reader)?;
+        limits::len(len)?;
+        limits::alloc::<u8>(len)?;
+
+        let payload = limits::read_vec(&mut reader, len).map_err(|err| err.trace(None, read1))?;
+        let (signer, read2) =
+            PublicKey::decode_with_read_from(&mut reader).map_err(|err| err.trace(None, read1 + len))?;
+        let (signature, read3) =
+            Signature::decode_with_read_from(reader).map_err(|err| err.trace(None, read1 + len + read2))?;
+
+        Ok((Unverified { payload, signer, signature }, read1 + len + read2 + read3))
+    }
+
+    fn precheck(&self) -> bool {
+        let r = CompressedEdwardsY::from_slice(&self.signature.as_ref()[..32]);
+        match (CompressedEdwardsY::from_slice(self.signer.as_bytes()).decompress(), r.decompress()) {
+            (Some(a), Some(point)) => !a.is_small_order() && !point.is_small_order() && point.compress() == r,
+            _ => false,
+        }
+    }
+
+    fn verify(&self) -> bool {
+        self.precheck() && self.signer.verify_strict(&self.payload, &self.signature).is_ok()
+    }
+
+    fn open<T: Decode>(self) -> Result<Signed<T>, T::Error>
+    where
+        T::Error: From<Error>,
+    {
+        let (val, read) = T::decode_with_read(&self.payload)?;
+        if read != self.payload.len() {
+            return Err(Error::invalid_data("trailing bytes in signed payload").into());
+        }
+
+        Ok(Signed { val, payload: self.payload, signer: self.signer, signature: self.signature })
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                               impl {En,De}code for Batch<T>                                │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+impl<T: Encode> Encode for Batch<T>
+where
+    T::Error: From<Error>,
+{
+    type Error = T::Error;
+
+    fn size(&self) -> Result<usize, Self::Error> {
+        Ok(self.fast_size())
+    }
+
+    fn fast_size(&self) -> usize {
+        U16::fast_size(self.0.len()) + self.0.iter().map(Signed::fast_size).sum::<usize>()
+    }
+
+    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        U16::encode_into(self.0.len(), &mut writer)?;
+        for signed in &self.0 {
+            signed.encode_into(&mut writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: Decode> Decode for Batch<T>
+where
+    T::Error: From<Error> + Trace,
+{
+    fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
+        let _depth = Depth::enter()?;
+        let (len, mut read) = U16::decode_with_read_from(&mut reader)?;
+        limits::len(len)?;
+        limits::alloc::<Signed<T>>(len)?;
+
+        let mut offsets = Vec::with_capacity(limits::capacity::<usize>(len));
+        let mut unverified = Vec::with_capacity(limits::capacity::<Unverified>(len));
+        for idx in 0..len {
+            let (signed, readb) = Unverified::decode_with_read_from(&mut reader)
+                .map_err(|err| err.trace(Some(Segment::Index(idx)), read))?;
+            offsets.push(read);
+            unverified.push(signed);
+            read += readb;
+        }
+
+        let payloads = unverified.iter().map(|signed| &signed.payload[..]).collect::<Vec<_>>();
+        let signatures = unverified.iter().map(|signed| signed.signature).collect::<Vec<_>>();
+        let signers = unverified.iter().map(|signed| signed.signer).collect::<Vec<_>>();
+        let valid = unverified.iter().all(Unverified::precheck)
+            && ed25519::verify_batch(&payloads, &signatures, &signers).is_ok();
+        if !valid {
+            // Find out which signature is invalid.
+            for (idx, signed) in unverified.iter().enumerate() {
+                if !signed.verify() {
+                    let err = Error::invalid_data("invalid signature");
+                    return Err(err.trace(Some(Segment::Index(idx)), offsets[idx]).into());
+                }
+            }
+        }
+
+        let mut batch = Vec::with_capacity(len);
+        for (idx, signed) in unverified.into_iter().enumerate() {
+            batch.push(signed.open::<T>().map_err(|err: T::Error| err.trace(Some(Segment::Index(idx)), offsets[idx]))?);
+        }
+
+        Ok((Batch(batch), read))
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                             impl Schema for {Signed,Batch}<T>                              │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// The payload is opaque since it has to be kept as-is for the signature to be verified.
+impl<T> Schema for Signed<T> {
+    fn schema() -> Layout {
+        Layout::Struct {
+            name: "Signed",
+            fields: Vec::from([
+                Field {
+                    name: "payload",
+                    layout: Layout::Seq { prefix: Box::new(U32::schema()), elem: Box::new(u8::schema()) },
+                },
+                Field { name: "signer", layout: PublicKey::schema() },
+                Field { name: "signature", layout: Signature::schema() },
+            ]),
+        }
+    }
+}
+
+impl<T> Schema for Batch<T> {
+    fn schema() -> Layout {
+        Layout::Seq { prefix: Box::new(U16::schema()), elem: Box::new(Signed::<T>::schema()) }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(test)]
+#[test]
+fn signatures() {
+    use crate::ErrorKind;
+    use rand::rngs::OsRng;
+
+    let keypair = Keypair::generate(&mut OsRng);
+    let signed = Signed::sign(&keypair, (1u8, 2u32)).unwrap();
+    let encoded = signed.encode().unwrap();
+    assert_eq!(encoded.len(), 4 + 5 + 32 + 64);
+    assert_eq!(Signed::<(u8, u32)>::decode(&encoded).unwrap(), signed);
+
+    let mut tampered = encoded.clone();
+    tampered[4] = 2;
+    let err = Signed::<(u8, u32)>::decode(&tampered).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidData { context: "invalid signature" }));
+
+    let batch = Batch((0..4u8).map(|idx| Signed::sign(&keypair, (idx, 0u32)).unwrap()).collect());
+    let mut encoded = batch.encode().unwrap();
+    assert_eq!(Batch::<(u8, u32)>::decode(&encoded).unwrap(), batch);
+
+    encoded[2 + 2 * 105 + 4] = 0;
+    let err = Batch::<(u8, u32)>::decode(&encoded).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidData { context: "invalid signature" }));
+    assert_eq!(err.path().copied().collect::<Vec<_>>(), [Segment::Index(2)]);
+    assert_eq!(err.offset(), 2 + 2 * 105);
+
+    // Signed with the identity as both the public key and `R` (and zero as `s`), which any message
+    // verifies against unless small order points are rejected.
+    let mut weak = Signed::sign(&keypair, (0u8, 0u32)).unwrap().encode().unwrap();
+    weak[9..].fill(0);
+    weak[9] = 1;
+    weak[9 + 32] = 1;
+    let err = Signed::<(u8, u32)>::decode(&weak).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidData { context: "invalid signature" }));
+
+    let mut encoded = batch.encode().unwrap();
+    encoded[2 + 105..2 + 2 * 105].copy_from_slice(&weak);
+    for _ in 0..16 {
+        let err = Batch::<(u8, u32)>::decode(&encoded).unwrap_err();
+        assert_eq!(err.path().copied().collect::<Vec<_>>(), [Segment::Index(1)]);
+    }
+}
@@ -0,0 +1,161 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use crate::io::{Read, Write};
+use crate::limits;
+use crate::prefix::{self, Prefix, U32};
+use crate::schema::{Field, Layout, Schema};
+use crate::{Decode, Encode, Error, Trace};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                  fn {en,de}code_variant()                                  │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Helpers for hand-written sum types, which encode a variant as its tag followed by its payload
+// (like derived enums do).
+pub fn variant_size<G, T>(tag: G, val: &T) -> Result<usize, T::Error>
+where
+    G: Encode,
+    T: Encode + ?Sized,
+{
+    Ok(tag.fast_size() + val.size()?)
+}
+
+pub fn variant_fast_size<G, T>(tag: G, val: &T) -> usize
+where
+    G: Encode,
+    T: Encode + ?Sized,
+{
+    tag.fast_size() + val.fast_size()
+}
+
+pub fn encode_variant<G, T, W>(tag: G, val: &T, mut writer: W) -> Result<(), T::Error>
+where
+    G: Encode<Error = Error>,
+    T: Encode + ?Sized,
+    T::Error: From<Error>,
+    W: Write,
+{
+    tag.encode_into(&mut writer)?;
+    val.encode_into(writer)
+}
+
+// Decodes the payload of a variant whose tag (`read` bytes long) was just decoded, wrapping it with
+// `variant` (e.g. `IpAddr::V4`).
+pub fn decode_variant<T, V, R, F>(read: usize, reader: R, variant: F) -> Result<(V, usize), T::Error>
+where
+    T: Decode,
+    T::Error: Trace,
+    R: Read,
+    F: FnOnce(T) -> V,
+{
+    let (val, readb) = T::decode_with_read_from(reader).map_err(|err| err.trace(None, read))?;
+    Ok((variant(val), read + readb))
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                fn {decode,borrow}_payload()                                │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// Enums with an unknown-variant fallback prefix the payload of each variant with its `U32` length,
+// so that peers which don't know a variant can skip it (or keep it as an `Unknown`) and peers
+// which know an older version of it can ignore the fields it gained since then. Both functions
+// return the payload and the length of its prefix.
+pub fn decode_payload<R: Read>(mut reader: R) -> Result<(Vec<u8>, usize), Error> {
+    let (len, read) = U32::decode_with_read_from(&mut reader)?;
+    limits::len(len)?;
+    limits::alloc::<u8>(len)?;
+
+    let payload = limits::read_vec(reader, len).map_err(|err| err.trace(None, read))?;
+    Ok((payload, read))
+}
+
+pub fn borrow_payload(buf: &[u8]) -> Result<(&[u8], usize), Error> {
+    let (len, read) = U32::decode_with_read_from(buf)?;
+    match buf[read..].get(..len) {
+        Some(payload) => Ok((payload, read)),
+        None => Err(Error::unexpected_eof(len - (buf.len() - read)).trace(None, read)),
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                     struct Unknown<G>                                      │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+// A variant whose tag isn't known, kept as-is so that it can be passed along and re-encoded. Used
+// as the only field of an enum's `#[f0rm47(unknown)]` variant.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Unknown<G = u8> {
+    pub tag: G,
+    pub payload: Vec<u8>,
+}
+
+impl<G: Encode<Error = Error>> Encode for Unknown<G> {
+    type Error = Error;
+
+    fn size(&self) -> Result<usize, Self::Error> {
+        Ok(self.tag.size()? + prefix::size::<U32, [u8]>(&self.payload)?)
+    }
+
+    fn fast_size(&self) -> usize {
+        self.tag.fast_size() + prefix::fast_size::<U32, [u8]>(&self.payload)
+    }
+
+    fn encode_into<W: Write>(&self, mut writer: W) -> Result<(), Self::Error> {
+        self.tag.encode_into(&mut writer)?;
+        prefix::encode_into::<U32, [u8], _>(&self.payload, writer)
+    }
+}
+
+impl<G: Decode<Error = Error>> Decode for Unknown<G> {
+    fn decode_with_read_from<R: Read>(mut reader: R) -> Result<(Self, usize), Self::Error> {
+        let (tag, read) = G::decode_with_read_from(&mut reader)?;
+        let (payload, readb) = decode_payload(reader).map_err(|err| err.trace(None, read))?;
+        let len = payload.len();
+
+        Ok((Unknown { tag, payload }, read + readb + len))
+    }
+}
+
+impl<G: Schema> Schema for Unknown<G> {
+    fn schema() -> Layout {
+        Layout::Struct {
+            name: "Unknown",
+            fields: Vec::from([
+                Field { name: "tag", layout: G::schema() },
+                Field {
+                    name: "payload",
+                    layout: Layout::Seq { prefix: Box::new(U32::schema()), elem: Box::new(u8::schema()) },
+                },
+            ]),
+        }
+    }
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(test)]
+#[test]
+fn unknown() {
+    let unknown = Unknown { tag: 7u16, payload: Vec::from([1, 2]) };
+    let encoded = unknown.encode().unwrap();
+    assert_eq!(encoded, [7, 0, 2, 0, 0, 0, 1, 2]);
+    assert_eq!(Unknown::<u16>::decode_with_read(&encoded).unwrap(), (unknown, 8));
+
+    assert_eq!(borrow_payload(&encoded[2..]).unwrap(), (&[1, 2][..], 4));
+    assert_eq!(decode_payload(&encoded[2..7]).unwrap_err().offset(), 4);
+    assert_eq!(borrow_payload(&encoded[2..7]).unwrap_err().offset(), 4);
+}
@@ -0,0 +1,188 @@
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                                                                            │ *
+ * │ This Source Code Form is subject to the terms of the Mozilla Public                        │ *
+ * │ License, v. 2.0. If a copy of the MPL was not distributed with this                        │ *
+ * │ file, You can obtain one at http://mozilla.org/MPL/2.0/.                                   │ *
+ * │                                                                                            │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          Imports                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+use crate::borrow::owned;
+use crate::io::{Read, Write};
+use crate::{Decode, Encode, Error};
+use core::mem;
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                 struct {Varint,ZigZag}<T>                                  │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Varint<T>(pub T);
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ZigZag<T>(pub T);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                       fn {en,de}code                                       │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+pub(crate) fn fast_size(val: u128) -> usize {
+    let bits = 128 - val.leading_zeros() as usize;
+    if bits == 0 {
+        1
+    } else {
+        bits.div_ceil(7)
+    }
+}
+
+pub(crate) fn encode_into<W: Write>(mut val: u128, mut writer: W) -> Result<(), Error> {
+    let mut buf = [0; 19];
+    let mut idx = 0;
+
+    loop {
+        buf[idx] = (val & 0x7f) as u8;
+        val >>= 7;
+
+        if val == 0 {
+            break;
+        }
+
+        buf[idx] |= 0x80;
+        idx += 1;
+    }
+
+    Ok(writer.write_all(&buf[..=idx])?)
+}
+
+pub(crate) fn decode_with_read_from<R: Read>(bits: usize, mut reader: R) -> Result<(u128, usize), Error> {
+    let mut val = 0;
+    for idx in 0..bits.div_ceil(7) {
+        let (byte, _) = u8::decode_with_read_from(&mut reader)?;
+
+        let left = bits - idx * 7;
+        if left < 8 && byte >> left != 0 {
+            return Err(Error::invalid_data("varint overflow"));
+        } else if idx > 0 && byte == 0 {
+            return Err(Error::invalid_data("overlong varint"));
+        }
+
+        val |= ((byte & 0x7f) as u128) << (idx * 7);
+        if byte & 0x80 == 0 {
+            return Ok((val, idx + 1));
+        }
+    }
+
+    unreachable!()
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                           Macros                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+macro_rules! varint {
+    ($unsigned:ty, $signed:ty) => {
+        impl Encode for Varint<$unsigned> {
+            type Error = Error;
+
+            fn fast_size(&self) -> usize {
+                fast_size(self.0 as u128)
+            }
+
+            fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+                encode_into(self.0 as u128, writer)
+            }
+        }
+
+        impl Decode for Varint<$unsigned> {
+            fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+                let (val, read) = decode_with_read_from(mem::size_of::<$unsigned>() * 8, reader)?;
+                Ok((Varint(val as $unsigned), read))
+            }
+        }
+
+        impl Encode for ZigZag<$signed> {
+            type Error = Error;
+
+            fn fast_size(&self) -> usize {
+                Varint(zigzag!(self.0, $unsigned, $signed)).fast_size()
+            }
+
+            fn encode_into<W: Write>(&self, writer: W) -> Result<(), Self::Error> {
+                Varint(zigzag!(self.0, $unsigned, $signed)).encode_into(writer)
+            }
+        }
+
+        impl Decode for ZigZag<$signed> {
+            fn decode_with_read_from<R: Read>(reader: R) -> Result<(Self, usize), Self::Error> {
+                let (Varint(val), read) = Varint::<$unsigned>::decode_with_read_from(reader)?;
+                Ok((ZigZag(((val >> 1) as $signed) ^ -((val & 1) as $signed)), read))
+            }
+        }
+    };
+}
+
+macro_rules! zigzag {
+    ($val:expr, $unsigned:ty, $signed:ty) => {
+        (($val << 1) ^ ($val >> (mem::size_of::<$signed>() * 8 - 1))) as $unsigned
+    };
+}
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                        varint!(..)                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+varint!(u8, i8);
+varint!(u16, i16);
+varint!(u32, i32);
+varint!(u64, i64);
+varint!(u128, i128);
+varint!(usize, isize);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                         owned!(..)                                         │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+owned!(Varint<u8>, Varint<u16>, Varint<u32>, Varint<u64>, Varint<u128>, Varint<usize>);
+owned!(ZigZag<i8>, ZigZag<i16>, ZigZag<i32>, ZigZag<i64>, ZigZag<i128>, ZigZag<isize>);
+
+/* ┌────────────────────────────────────────────────────────────────────────────────────────────┐ *\
+ * │                                          #[test]                                           │ *
+\* └────────────────────────────────────────────────────────────────────────────────────────────┘ */
+
+#[cfg(test)]
+#[test]
+fn varint() {
+    for (val, bytes) in [(0u64, &[0x00][..]), (127, &[0x7f]), (128, &[0x80, 0x01]), (300, &[0xac, 0x02])] {
+        let encoded = Varint(val).encode().unwrap();
+        assert_eq!(encoded, bytes);
+        assert_eq!(Varint(val).fast_size(), bytes.len());
+        assert_eq!(Varint::<u64>::decode_with_read(bytes).unwrap(), (Varint(val), bytes.len()));
+    }
+
+    let encoded = Varint(u64::MAX).encode().unwrap();
+    assert_eq!(encoded.len(), 10);
+    assert_eq!(Varint::<u64>::decode(&encoded).unwrap(), Varint(u64::MAX));
+    assert_eq!(Varint(u128::MAX).encode().unwrap().len(), 19);
+
+    assert!(Varint::<u64>::decode(&[0x80, 0x00]).is_err());
+    assert!(Varint::<u64>::decode(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02]).is_err());
+    assert!(Varint::<u16>::decode(&[0xff, 0xff, 0x04]).is_err());
+    assert!(Varint::<u16>::decode(&[0xff, 0xff]).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn zigzag() {
+    for (val, bytes) in [(0i32, &[0x00][..]), (-1, &[0x01]), (1, &[0x02]), (-64, &[0x7f]), (64, &[0x80, 0x01])] {
+        assert_eq!(ZigZag(val).encode().unwrap(), bytes);
+        assert_eq!(ZigZag::<i32>::decode(bytes).unwrap(), ZigZag(val));
+    }
+
+    for val in [i64::MIN, i64::MAX] {
+        let encoded = ZigZag(val).encode().unwrap();
+        assert_eq!(ZigZag::<i64>::decode(&encoded).unwrap(), ZigZag(val));
+    }
+}